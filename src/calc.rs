@@ -1,40 +1,45 @@
+use crate::correlation::Correlation;
 use crate::dim;
-use crate::dim::{Basic, Reviewed, ReviewedStack, Stack};
+use crate::dim::{Basic, Contributor, Reviewed, Stack};
 use crate::dist;
+use crate::error::{Error, Result};
+use crate::part::Part;
+use crate::sobol::{self, Sobol};
 use crate::stats;
 use crate::tol::Bilateral;
+use crate::utils::{cast, to_f64};
+use crate::validate;
 
-pub fn closed(stack: &Stack) -> Basic {
-    let nominal: f32 = stack
-        .dims
-        .iter()
-        .map(|dim| dim.absolute_nominal_adj())
-        .sum();
-    let tolerance: Bilateral = if nominal < 0.0 {
+use num_traits::Float;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+pub(crate) fn sum<T: Float>(iter: impl Iterator<Item = T>) -> T {
+    iter.fold(T::zero(), |acc, x| acc + x)
+}
+
+pub fn closed<T: Float>(stack: &Stack<T>) -> Basic<T> {
+    let nominal: T = sum(stack.basics().map(|dim| dim.absolute_nominal_adj()));
+    let tolerance: Bilateral<T> = if nominal < T::zero() {
         Bilateral::unequal(
-            -stack
-                .dims
-                .iter()
-                .map(|dim| dim.absolute_lower_tol())
-                .sum::<f32>(),
-            -stack
-                .dims
-                .iter()
-                .map(|dim| dim.absolute_upper_tol())
-                .sum::<f32>(),
+            -sum(stack.basics().map(|dim| dim.absolute_lower_tol())),
+            -sum(stack.basics().map(|dim| dim.absolute_upper_tol())),
         )
     } else {
         Bilateral::unequal(
-            stack.dims.iter().map(|dim| dim.absolute_upper_tol()).sum(),
-            stack.dims.iter().map(|dim| dim.absolute_lower_tol()).sum(),
+            sum(stack.basics().map(|dim| dim.absolute_upper_tol())),
+            sum(stack.basics().map(|dim| dim.absolute_lower_tol())),
         )
     };
     Basic {
         nom: dim::Vec1D::new(nominal),
         tol: tolerance,
-        a: 1.0,
+        a: T::one(),
         name: format!("{} - Closed Analysis", stack.name),
         desc: "".to_owned(),
+        material: None,
+        part: None,
+        feature: None,
     }
 }
 
@@ -42,20 +47,21 @@ pub fn closed(stack: &Stack) -> Basic {
 /// This results in a Bilateral dimension with a tolerance that is the sum of
 /// the component tolerances. It states that in any combination of tolerances,
 /// you can be sure the result will be within the this resulting tolerance.
-pub fn wc(stack: &Stack) -> Basic {
-    let mean: f32 = stack.dims.iter().map(|dim| dim.absolute_median_adj()).sum();
-    let t_wc: f32 = stack
-        .dims
-        .iter()
-        .map(|dim| ((dim.tol.t() / 2.0) * dim.a).abs())
-        .sum();
+pub fn wc<T: Float>(stack: &Stack<T>) -> Basic<T> {
+    let mean: T = sum(stack.basics().map(|dim| dim.absolute_median_adj()));
+    let t_wc: T = sum(stack
+        .basics()
+        .map(|dim| ((dim.tol.t() / cast(2.0)) * dim.a).abs()));
     let tolerance = Bilateral::symmetric(t_wc);
     Basic {
         nom: dim::Vec1D::new(mean),
         tol: tolerance,
-        a: 1.0,
+        a: T::one(),
         name: format!("{} - WC Analysis", stack.name),
         desc: "".to_owned(),
+        material: None,
+        part: None,
+        feature: None,
     }
 }
 
@@ -80,73 +86,554 @@ pub fn wc(stack: &Stack) -> Basic {
 /// See:
 ///     - Dimensioning and Tolerancing Handbook, McGraw Hill
 ///     - http://files.engineering.com/getfile.aspx?folder=69759f43-e81a-4801-9090-a0c95402bfc0&file=RSS_explanation.GIF
-pub fn rss(stack: &Stack) -> Basic {
-    let d_g: f32 = stack.dims.iter().map(|dim| dim.absolute_median_adj()).sum();
-    let t_rss: f32 = stats::rss(
+///
+/// Fails if the stack's correlations are not valid, see `Stack::correlation`.
+pub fn rss<T: Float>(stack: &Stack<T>) -> Result<Basic<T>> {
+    let d_g: T = sum(stack.basics().map(|dim| dim.absolute_median_adj()));
+    let t_rss: T = correlated_rss(
+        stack.correlation()?.as_ref(),
         &stack
-            .dims
-            .iter()
-            .map(|dim| dim.nom.x.signum() * (dim.tol.t() / 2.0) * dim.a)
-            .collect(),
+            .basics()
+            .map(|dim| dim.nom.x.signum() * (dim.tol.t() / cast(2.0)) * dim.a)
+            .collect::<Vec<T>>(),
     );
     let tolerance = Bilateral::symmetric(t_rss);
-    Basic {
+    Ok(Basic {
         nom: dim::Vec1D::new(d_g),
         tol: tolerance,
-        a: 1.0,
+        a: T::one(),
         name: format!("{} - RSS Analysis", stack.name),
         desc: "(assuming inputs with Normal Dist. & uniform SD)".to_owned(),
-    }
+        material: None,
+        part: None,
+        feature: None,
+    })
 }
 
 /// Basically RSS with a coefficient modifier that makes the tolerance tighter.
-pub fn mrss(stack: &Stack) -> Basic {
-    let d_g: f32 = stack.dims.iter().map(|dim| dim.absolute_median_adj()).sum();
-    let t_wc: f32 = stack
-        .dims
-        .iter()
-        .map(|dim| ((dim.tol.t() / 2.0) * dim.a).abs())
-        .sum();
-    let t_rss: f32 = stats::rss(
+///
+/// The correction factor divides by `sqrt(n) - 1`, so at least two dims are
+/// required.
+pub fn mrss<T: Float>(stack: &Stack<T>) -> Result<Basic<T>> {
+    if stack.dims.len() < 2 {
+        return Err(Error::TooFewDims {
+            analysis: "MRSS",
+            required: 2,
+            found: stack.dims.len(),
+        });
+    }
+    let d_g: T = sum(stack.basics().map(|dim| dim.absolute_median_adj()));
+    let t_wc: T = sum(stack
+        .basics()
+        .map(|dim| ((dim.tol.t() / cast(2.0)) * dim.a).abs()));
+    let t_rss: T = correlated_rss(
+        stack.correlation()?.as_ref(),
         &stack
-            .dims
-            .iter()
-            .map(|dim| dim.nom.x.signum() * dim.a * (dim.tol.t() / 2.0))
-            .collect(),
+            .basics()
+            .map(|dim| dim.nom.x.signum() * dim.a * (dim.tol.t() / cast(2.0)))
+            .collect::<Vec<T>>(),
     );
-    let n: f32 = stack.dims.len() as f32;
-    let c_f: f32 = (0.5 * (t_wc - t_rss)) / (t_rss * (n.sqrt() - 1.0)) + 1.0;
-    let t_mrss: f32 = c_f * t_rss;
+    let n: T = T::from(stack.dims.len()).unwrap();
+    let t_mrss: T = if t_rss == T::zero() {
+        T::zero()
+    } else {
+        stats::c_f(t_rss, t_wc, n) * t_rss
+    };
     let tolerance = Bilateral::symmetric(t_mrss);
-    Basic {
+    Ok(Basic {
         // dir,
         // nom: d_g,
         nom: dim::Vec1D::new(d_g),
         tol: tolerance,
-        a: 1.0,
+        a: T::one(),
         name: format!("{} - MRSS Analysis", stack.name),
         desc: "(assuming inputs with Normal Dist. & uniform SD)".to_owned(),
+        material: None,
+        part: None,
+        feature: None,
+    })
+}
+
+/// Bender's inflated RSS, 1.5 times the RSS tolerance, allowing for mean
+/// shifts and non-normal processes. With few dims this can exceed the WC
+/// tolerance.
+///
+/// See: A. Bender, "Statistical Tolerancing as it Relates to Quality
+/// Control and the Designer", SAE 680490, 1968.
+pub fn bender<T: Float>(stack: &Stack<T>) -> Result<Basic<T>> {
+    let mut basic = rss(stack)?;
+    basic.tol = Bilateral::symmetric(cast::<T>(1.5) * basic.tol.t() / cast(2.0));
+    basic.name = format!("{} - Bender Analysis", stack.name);
+    basic.desc = "(1.5 x RSS)".to_owned();
+    Ok(basic)
+}
+
+/// Mean shift RSS, where the mean of each dim may drift by a fraction
+/// `shift` of its tolerance. The drifts add worst case and the rest of each
+/// tolerance adds by RSS: `sum(m |a_i t_i|) + rss((1 - m) a_i t_i)`.
+///
+/// A `shift` of 0 is RSS and 1 is WC; 0.2 to 0.3 is typical.
+///
+/// See: K. W. Chase and W. H. Greenwood, "Design Issues in Mechanical
+/// Tolerance Analysis", Manufacturing Review, 1988.
+pub fn mean_shift_rss<T: Float>(stack: &Stack<T>, shift: T) -> Result<Basic<T>> {
+    if shift.is_nan() || shift < T::zero() || shift > T::one() {
+        return Err(Error::InvalidShift(to_f64(shift)));
     }
+    let d_g: T = sum(stack.basics().map(|dim| dim.absolute_median_adj()));
+    let terms: Vec<T> = stack
+        .basics()
+        .map(|dim| dim.nom.x.signum() * (dim.tol.t() / cast(2.0)) * dim.a)
+        .collect();
+    let drift = shift * sum(terms.iter().map(|t| t.abs()));
+    let variation = correlated_rss(
+        stack.correlation()?.as_ref(),
+        &terms
+            .iter()
+            .map(|&t| (T::one() - shift) * t)
+            .collect::<Vec<T>>(),
+    );
+    let mut basic = Basic::from_limits(
+        &format!("{} - Mean Shift RSS Analysis", stack.name),
+        d_g,
+        d_g - drift - variation,
+        d_g + drift + variation,
+    );
+    basic.desc = format!("(mean shift of {} x tolerance)", to_f64(shift));
+    Ok(basic)
 }
 
-pub fn six_sigma(stack: &ReviewedStack, at: f32) -> Reviewed {
-    let mean: f32 = stack
+/// Statistical worst case, from the process data of every dim.
+///
+/// The mean of each dim may sit anywhere that keeps `target_process_sigma`
+/// standard deviations within its limits, or at its known mean if that is
+/// further off center. These mean shifts add worst case, and the remaining
+/// variation adds by RSS at +/- `at` sigma.
+///
+/// All dims must be `Reviewed`.
+pub fn swc<T: Float>(stack: &Stack<T>, at: T) -> Result<Basic<T>> {
+    if stack.dims.is_empty() {
+        return Err(Error::TooFewDims {
+            analysis: "SWC",
+            required: 1,
+            found: 0,
+        });
+    }
+    let rdims = stack
         .dims
         .iter()
-        .map(|rdim| rdim.dim.absolute_median_adj())
-        .sum();
-    let std_dev: f32 = stats::rss(&stack.dims.iter().map(|dim| dim.std_dev_eff()).collect());
+        .map(|dim| {
+            dim.reviewed()
+                .ok_or_else(|| Error::MissingProcessData(dim.name().to_owned()))
+        })
+        .collect::<Result<Vec<&Reviewed<T>>>>()?;
+    let mut drift = T::zero();
+    let mut terms = Vec::with_capacity(rdims.len());
+    for rdim in &rdims {
+        let (mean, std_dev) = match &rdim.distribution {
+            Some(d) => (d.mean(), d.std_dev()),
+            None => (rdim.dim.absolute_median(), T::zero()),
+        };
+        let allowed = rdim.dim.tol.t() / cast(2.0) - rdim.target_process_sigma * std_dev;
+        let known = (mean - rdim.dim.absolute_median()).abs();
+        drift = drift + (allowed.max(known).max(T::zero()) * rdim.dim.a).abs();
+        terms.push(rdim.dim.nom.x.signum() * rdim.dim.a * std_dev);
+    }
+    let d_g: T = sum(rdims.iter().map(|rdim| rdim.dim.absolute_median_adj()));
+    let variation = at * correlated_rss(stack.correlation()?.as_ref(), &terms);
+    let mut basic = Basic::from_limits(
+        &format!("{} - SWC Analysis", stack.name),
+        d_g,
+        d_g - drift - variation,
+        d_g + drift + variation,
+    );
+    basic.desc = "(worst case mean shifts with RSS variation)".to_owned();
+    Ok(basic)
+}
+
+/// Statistical analysis using the process data of every dim.
+///
+/// Each dim contributes the standard deviation of its distribution, of any
+/// type, see `Reviewed::std_dev_eff`. The assembly is approximated as normal,
+/// which is poor in the tails when a few non-normal dims dominate. The
+/// result then carries `validate::Warning::NonNormalAssembly` in its `desc`;
+/// `convolve` gives the exact distribution.
+///
+/// All dims must be `Reviewed`.
+pub fn six_sigma<T: Float>(stack: &Stack<T>, at: T) -> Result<Reviewed<T>> {
+    if stack.dims.is_empty() {
+        return Err(Error::TooFewDims {
+            analysis: "'6 Sigma'",
+            required: 1,
+            found: 0,
+        });
+    }
+    let rdims = stack
+        .dims
+        .iter()
+        .map(|dim| {
+            dim.reviewed()
+                .ok_or_else(|| Error::MissingProcessData(dim.name().to_owned()))
+        })
+        .collect::<Result<Vec<&Reviewed<T>>>>()?;
+    let mean: T = sum(rdims.iter().map(|rdim| rdim.dim.absolute_median_adj()));
+    let std_dev: T = correlated_rss(
+        stack.correlation()?.as_ref(),
+        &rdims
+            .iter()
+            .map(|rdim| rdim.dim.nom.x.signum() * rdim.dim.a * rdim.std_dev_eff())
+            .collect::<Vec<T>>(),
+    );
     let tolerance = Bilateral::symmetric(std_dev * at);
-    let dist = dist::Normal::new(mean, std_dev);
-    Reviewed {
+    let dist = dist::Normal::new(mean, std_dev)?;
+    let desc = match validate::check_normality(&rdims) {
+        Some(warning) => format!("(Normal approximation of the assembly, warning: {warning})"),
+        None => "(Normal approximation of the assembly)".to_owned(),
+    };
+    Ok(Reviewed {
         dim: Basic {
             nom: dim::Vec1D::new(mean),
             tol: tolerance,
-            a: 1.0,
+            a: T::one(),
             name: format!("{} - '6 Sigma' Analysis", stack.name),
-            desc: "(assuming inputs with Normal Dist.)".to_owned(),
+            desc,
+            material: None,
+            part: None,
+            feature: None,
         },
         target_process_sigma: at,
         distribution: Some(dist::Distribution::Normal(dist)),
+    })
+}
+
+/// Monte Carlo simulation of the stack.
+///
+/// Each dim is sampled from its `Contributor::distribution`, so reviewed
+/// dims use their process data and the rest are assumed normal at +/- 3
+/// sigma. Correlated dims are sampled through a normal copula, which gives
+/// exactly the stack's correlation between normal dims. The result carries
+/// the sampled assembly as an empirical distribution, with limits at the
+/// quantiles that a normal distribution would have at +/- `at` sigma.
+pub fn monte_carlo<T: Float>(
+    stack: &Stack<T>,
+    at: T,
+    samples: usize,
+    seed: u64,
+) -> Result<Reviewed<T>> {
+    let dims: Vec<&Contributor<T>> = stack.dims.iter().collect();
+    simulate(
+        &stack.name,
+        &dims,
+        |x| sum(stack.basics().zip(x).map(|(dim, &x)| x * dim.a)),
+        stack.correlation()?.as_ref(),
+        at,
+        samples,
+        seed,
+    )
+}
+
+/// Share of one dim in the variation of a stack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contribution<T = f64> {
+    pub name: String,
+    pub part: Option<Part>,
+    pub feature: Option<String>,
+    /// Fraction of the WC tolerance.
+    pub wc: T,
+    /// Fraction of the RSS variance. With correlation, each dim also takes
+    /// its covariance with the others, so a share can be negative.
+    pub rss: T,
+}
+
+/// Share of each dim in the WC tolerance and RSS variance of the stack.
+/// Each set of shares sums to one, unless the stack has no tolerance.
+pub fn contributions<T: Float>(stack: &Stack<T>) -> Result<Vec<Contribution<T>>> {
+    let terms: Vec<T> = stack
+        .basics()
+        .map(|dim| dim.nom.x.signum() * (dim.tol.t() / cast(2.0)) * dim.a)
+        .collect();
+    let correlation = stack.correlation()?;
+    let t_wc = sum(terms.iter().map(|t| t.abs()));
+    let t_rss = correlated_rss(correlation.as_ref(), &terms);
+    let share = |part: T, total: T| {
+        if total > T::zero() {
+            part / total
+        } else {
+            T::zero()
+        }
+    };
+    Ok(stack
+        .basics()
+        .enumerate()
+        .map(|(i, dim)| {
+            let covariance = match &correlation {
+                None => terms[i] * terms[i],
+                Some(correlation) => sum(terms
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &t)| correlation.get(i, j) * terms[i] * t)),
+            };
+            Contribution {
+                name: dim.name.clone(),
+                part: dim.part.clone(),
+                feature: dim.feature.clone(),
+                wc: share(terms[i].abs(), t_wc),
+                rss: share(covariance, t_rss * t_rss),
+            }
+        })
+        .collect())
+}
+
+/// Exact distribution of the stack, by numerical convolution of the
+/// distribution of every dim, see `Contributor::distribution`.
+///
+/// Each dim is discretized into its probability in each cell of a common
+/// grid, taken from the tail nearest the cell so that PPM level tails are
+/// not lost to cancellation, and the cells are convolved directly. Unlike
+/// `six_sigma` this keeps the shape of non-normal dims, and unlike
+/// `monte_carlo` it is deterministic. The result carries the assembly as a
+/// tabulated distribution, to check against a `Requirement`, with limits at
+/// the quantiles that a normal distribution would have at +/- `at` sigma.
+///
+/// Dims without spread, such as a single measurement, add as a fixed
+/// offset. Dims must be independent and at least one must vary.
+pub fn convolve<T: Float>(stack: &Stack<T>, at: T) -> Result<Reviewed<T>> {
+    const POINTS: usize = 4096;
+    if stack.dims.is_empty() {
+        return Err(Error::TooFewDims {
+            analysis: "Convolution",
+            required: 1,
+            found: 0,
+        });
+    }
+    check_independent(stack.correlation()?.as_ref(), "Convolution")?;
+    let mut offset = T::zero();
+    let mut dists = Vec::new();
+    for dim in &stack.dims {
+        let a = dim.basic().a;
+        match dim.distribution().map(|d| d.scaled(a)) {
+            Some(d) if a != T::zero() => {
+                let (lower, upper) = d.support();
+                if upper > lower {
+                    dists.push(d);
+                } else {
+                    // a point mass, e.g. a single measurement
+                    offset = offset + d.mean();
+                }
+            }
+            _ => offset = offset + dim.basic().absolute_median_adj(),
+        }
+    }
+    if dists.is_empty() {
+        return Err(Error::NoVariation);
+    }
+    let width = sum(dists.iter().map(|d| {
+        let (lower, upper) = d.support();
+        upper - lower
+    }));
+    let h = width / cast(POINTS as f64);
+    let half = h / cast(2.0);
+
+    // probability in each cell, the first centered on start * h
+    let mut start: i64 = 0;
+    let mut masses = vec![T::one()];
+    for d in &dists {
+        let (lower, upper) = d.support();
+        let first = (lower / h).round().to_i64().unwrap();
+        let last = (upper / h).round().to_i64().unwrap();
+        let median = d.quantile(cast(0.5));
+        let cells: Vec<T> = (first..=last)
+            .map(|j| {
+                let x = h * cast(j as f64);
+                if x > median {
+                    d.sf(x - half) - d.sf(x + half)
+                } else {
+                    d.cdf(x + half) - d.cdf(x - half)
+                }
+            })
+            .collect();
+        let mut convolved = vec![T::zero(); masses.len() + cells.len() - 1];
+        for (i, &m) in masses.iter().enumerate() {
+            for (k, &c) in cells.iter().enumerate() {
+                convolved[i + k] = convolved[i + k] + m * c;
+            }
+        }
+        masses = convolved;
+        start += first;
+    }
+    let x = (0..masses.len())
+        .map(|i| offset + h * cast((start + i as i64) as f64))
+        .collect();
+    let density = masses.iter().map(|&m| (m / h).max(T::zero())).collect();
+    let mut result = summarize(
+        &format!("{} - Convolution Analysis", stack.name),
+        dist::Distribution::Tabulated(dist::Tabulated::new(x, density)?),
+        at,
+    )?;
+    result.dim.desc = format!("({} point grid)", masses.len());
+    Ok(result)
+}
+
+/// Sobol indices of every dim of the stack, see `sobol::indices`.
+pub fn sobol<T: Float>(stack: &Stack<T>, samples: usize, seed: u64) -> Result<Vec<Sobol<T>>> {
+    let dims: Vec<&Contributor<T>> = stack.dims.iter().collect();
+    sobol::indices(
+        &dims,
+        |x| sum(stack.basics().zip(x).map(|(dim, &x)| x * dim.a)),
+        stack.correlation()?.as_ref(),
+        samples,
+        seed,
+    )
+}
+
+/// Sample `f` of the absolute values of `dims` and summarize the result as
+/// `monte_carlo` does.
+pub(crate) fn simulate<T: Float>(
+    name: &str,
+    dims: &[&Contributor<T>],
+    f: impl Fn(&[T]) -> T,
+    correlation: Option<&Correlation<T>>,
+    at: T,
+    samples: usize,
+    seed: u64,
+) -> Result<Reviewed<T>> {
+    if dims.is_empty() {
+        return Err(Error::TooFewDims {
+            analysis: "Monte Carlo",
+            required: 1,
+            found: 0,
+        });
+    }
+    let dists: Vec<Option<dist::Distribution<T>>> =
+        dims.iter().map(|dim| dim.distribution()).collect();
+    let n = dims.len();
+    let cholesky = correlation.map(|c| c.cholesky(n)).transpose()?;
+    let standard = dist::Normal::new(T::zero(), T::one())?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut p = vec![T::zero(); n];
+    let mut x = vec![T::zero(); n];
+    let mut values = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        for p in p.iter_mut() {
+            *p = T::from(rng.r#gen::<f64>()).unwrap();
+        }
+        if let Some(l) = &cholesky {
+            // correlate the sizes, so flip dims in the negative direction
+            let z: Vec<T> = p.iter().map(|&p| standard.quantile(p)).collect();
+            for (i, dim) in dims.iter().enumerate() {
+                let c = sum((0..=i).map(|k| l[i][k] * z[k]));
+                p[i] = standard.cdf(dim.basic().nom.x.signum() * c);
+            }
+        }
+        for (i, (dim, d)) in dims.iter().zip(&dists).enumerate() {
+            x[i] = match d {
+                Some(d) => d.quantile(p[i]),
+                None => dim.basic().absolute_median(),
+            };
+        }
+        values.push(f(&x));
+    }
+    let empirical = dist::Empirical::new(values)?;
+    let mut result = summarize(
+        &format!("{name} - Monte Carlo Analysis"),
+        dist::Distribution::Empirical(empirical),
+        at,
+    )?;
+    result.dim.desc = format!("({samples} samples)");
+    Ok(result)
+}
+
+/// Result carrying `distribution`, with limits at the quantiles that a
+/// normal distribution would have at +/- `at` sigma.
+pub(crate) fn summarize<T: Float>(
+    name: &str,
+    distribution: dist::Distribution<T>,
+    at: T,
+) -> Result<Reviewed<T>> {
+    let coverage = dist::Normal::new(T::zero(), T::one())?;
+    let lower = distribution.quantile(coverage.cdf(-at));
+    let upper = distribution.quantile(coverage.cdf(at));
+    Ok(Reviewed {
+        dim: Basic::from_limits(name, distribution.mean(), lower, upper),
+        target_process_sigma: at,
+        distribution: Some(distribution),
+    })
+}
+
+/// Fails for an `analysis` that needs independent dims if `correlation`
+/// correlates any pair of them.
+pub(crate) fn check_independent<T: Float>(
+    correlation: Option<&Correlation<T>>,
+    analysis: &'static str,
+) -> Result<()> {
+    if let Some(correlation) = correlation {
+        let n = correlation.len();
+        if (0..n).any(|i| (0..n).any(|j| i != j && correlation.get(i, j) != T::zero())) {
+            return Err(Error::CorrelatedDims(analysis));
+        }
+    }
+    Ok(())
+}
+
+/// Root sum square of one term per dim, with the correlation between them.
+pub(crate) fn correlated_rss<T: Float>(correlation: Option<&Correlation<T>>, terms: &[T]) -> T {
+    match correlation {
+        None => stats::rss(terms),
+        Some(correlation) => {
+            let n = terms.len();
+            sum((0..n)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .map(|(i, j)| correlation.get(i, j) * terms[i] * terms[j]))
+            .max(T::zero())
+            .sqrt()
+        }
     }
 }
+
+/// Half tolerance of each dim scaled by its sensitivity `a`.
+pub(crate) fn scaled_half_tols<T: Float>(dims: &[&Contributor<T>], a: &[T]) -> Vec<T> {
+    dims.iter()
+        .zip(a)
+        .map(|(dim, &a)| (dim.basic().tol.t() / cast(2.0)) * a)
+        .collect()
+}
+
+/// Median of an angle, which is a signed value rather than a size, so its
+/// limits are `nom + tol.lower` to `nom + tol.upper` whatever the sign of
+/// the nominal.
+pub(crate) fn signed_median<T: Float>(basic: &Basic<T>) -> T {
+    basic.nom.x + (basic.tol.lower + basic.tol.upper) / cast(2.0)
+}
+
+/// An angle as the equivalent dim in the direction convention of `Basic`,
+/// see `signed_median`. A normal distribution assumed from the limits is
+/// assumed again from the signed limits, while any other distribution is
+/// taken to be of the signed angle already and kept.
+pub(crate) fn signed_angle<T: Float>(dim: &Contributor<T>) -> Result<Contributor<T>> {
+    let mut dim = dim.clone();
+    if dim.basic().nom.x > T::zero() {
+        return Ok(dim);
+    }
+    let tol = &mut dim.basic_mut().tol;
+    *tol = Bilateral::unequal(-tol.lower, -tol.upper);
+    Ok(match dim {
+        Contributor::Reviewed(Reviewed {
+            dim,
+            target_process_sigma,
+            distribution: Some(dist::Distribution::Normal(_)),
+        }) => Reviewed::of_basic(dim, target_process_sigma)?.into(),
+        dim => dim,
+    })
+}
+
+/// Symmetric result of a stack linearized about `nominal`.
+pub(crate) fn linearized<T: Float>(name: &str, analysis: &str, nominal: T, t: T) -> Basic<T> {
+    let mut basic = Basic::from_limits(
+        &format!("{name} - {analysis} Analysis"),
+        nominal,
+        nominal - t,
+        nominal + t,
+    );
+    basic.desc = "(linearized about the median)".to_owned();
+    basic
+}
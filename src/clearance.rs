@@ -0,0 +1,176 @@
+use crate::dim::{Basic, Contributor, Direction, Reviewed};
+use crate::dist;
+use crate::error::{Error, Result};
+use crate::utils::{cast, total_cmp};
+
+use num_traits::Float;
+
+/// Where a part sits within the clearance it floats in.
+#[derive(Clone, Copy)]
+pub enum Shift {
+    /// Anywhere within the clearance, uniformly, centered on nominal.
+    Uniform,
+    /// Pushed against one side of the clearance, e.g. by gravity or a
+    /// spring, shifting by half the clearance in that direction.
+    Extreme(Direction),
+}
+
+/// Float of a part in the stack direction, from the clearance between a
+/// hole or slot and the pin or tab it sits on.
+///
+/// Both sizes are diameters or widths, so the part shifts by up to half
+/// the clearance each way. Entered in a stack, the float is centered on
+/// zero so that `calc::closed` keeps the nominal of the rest of the stack,
+/// and its tolerance spans the extreme positions at the largest clearance.
+#[derive(Clone)]
+pub struct Clearance<T = f64> {
+    pub name: String,
+    /// The internal feature, a hole or slot.
+    pub hole: Contributor<T>,
+    /// The external feature, a pin or tab.
+    pub pin: Contributor<T>,
+    pub shift: Shift,
+    pub a: T,
+}
+
+impl<T: Float> Clearance<T> {
+    /// Fails if the hole can never be larger than the pin.
+    pub fn new(
+        name: &str,
+        hole: impl Into<Contributor<T>>,
+        pin: impl Into<Contributor<T>>,
+        shift: Shift,
+    ) -> Result<Clearance<T>> {
+        let clearance = Clearance {
+            name: name.to_owned(),
+            hole: hole.into(),
+            pin: pin.into(),
+            shift,
+            a: T::one(),
+        };
+        if clearance.max() <= T::zero() {
+            return Err(Error::NoClearance);
+        }
+        Ok(clearance)
+    }
+
+    pub fn sensitivity(mut self, a: T) -> Clearance<T> {
+        self.a = a;
+        self
+    }
+
+    pub fn nominal(&self) -> T {
+        self.hole.basic().nom.x.abs() - self.pin.basic().nom.x.abs()
+    }
+
+    /// Smallest clearance, zero if the features may interfere.
+    pub fn min(&self) -> T {
+        (self.hole.basic().lower() - self.pin.basic().upper()).max(T::zero())
+    }
+
+    /// Largest clearance.
+    pub fn max(&self) -> T {
+        self.hole.basic().upper() - self.pin.basic().lower()
+    }
+
+    /// The float as a dimension spanning its extreme positions.
+    pub fn basic(&self) -> Basic<T> {
+        let half = |c: T| c / cast(2.0);
+        let (nominal, lower, upper) = match self.shift {
+            Shift::Uniform => (T::zero(), -half(self.max()), half(self.max())),
+            Shift::Extreme(Direction::Positive) => (
+                half(self.nominal().max(T::zero())),
+                half(self.min()),
+                half(self.max()),
+            ),
+            Shift::Extreme(Direction::Negative) => (
+                -half(self.nominal().max(T::zero())),
+                -half(self.max()),
+                -half(self.min()),
+            ),
+        };
+        let mut basic = Basic::from_limits(&self.name, nominal, lower, upper);
+        basic.a = self.a;
+        basic.desc = format!(
+            "(float between '{}' and '{}')",
+            self.hole.name(),
+            self.pin.name()
+        );
+        basic
+    }
+
+    /// Equal probability clearances from every pair of hole and pin sizes.
+    fn clearances(&self) -> Vec<T> {
+        const NODES: usize = 101;
+        let nodes = |dim: &Contributor<T>| -> Vec<T> {
+            match dim.distribution() {
+                Some(d) => (0..NODES)
+                    .map(|k| d.quantile(cast((k as f64 + 0.5) / NODES as f64)).abs())
+                    .collect(),
+                None => vec![dim.basic().nom.x.abs()],
+            }
+        };
+        let pins = nodes(&self.pin);
+        nodes(&self.hole)
+            .iter()
+            .flat_map(|&h| pins.iter().map(move |&p| (h - p).max(T::zero())))
+            .collect()
+    }
+
+    /// The float with its distribution.
+    ///
+    /// With `Shift::Uniform` the shift is uniform within each clearance
+    /// allowed by the hole and pin distributions, and fails if those never
+    /// leave any clearance. With `Shift::Extreme` it is half the clearance.
+    pub fn reviewed(&self) -> Result<Reviewed<T>> {
+        let distribution = match self.shift {
+            Shift::Uniform => self.uniform()?,
+            Shift::Extreme(direction) => {
+                let sign: T = direction.signum();
+                let halves = self
+                    .clearances()
+                    .into_iter()
+                    .map(|c| sign * c / cast(2.0))
+                    .collect();
+                dist::Distribution::Empirical(dist::Empirical::new(halves)?)
+            }
+        };
+        Ok(Reviewed {
+            dim: self.basic(),
+            target_process_sigma: cast(3.0),
+            distribution: Some(distribution),
+        })
+    }
+
+    /// Mixture of the uniform shifts over the clearances, tabulated.
+    fn uniform(&self) -> Result<dist::Distribution<T>> {
+        const POINTS: usize = 2001;
+        let mut halves: Vec<T> = self
+            .clearances()
+            .into_iter()
+            .filter(|&c| c > T::zero())
+            .map(|c| c / cast(2.0))
+            .collect();
+        if halves.is_empty() {
+            return Err(Error::NoClearance);
+        }
+        halves.sort_by(total_cmp);
+        // density of each uniform, summed from the widest down
+        let mut above = vec![T::zero(); halves.len() + 1];
+        for i in (0..halves.len()).rev() {
+            above[i] = above[i + 1] + T::one() / (halves[i] + halves[i]);
+        }
+        let widest = halves[halves.len() - 1];
+        let step = (widest + widest) / cast((POINTS - 1) as f64);
+        let x: Vec<T> = (0..POINTS)
+            .map(|i| -widest + step * cast(i as f64))
+            .collect();
+        let density = x
+            .iter()
+            .map(|&x| above[halves.partition_point(|&h| h < x.abs())])
+            .collect();
+        Ok(dist::Distribution::Tabulated(dist::Tabulated::new(
+            x, density,
+        )?))
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::{Error, Result};
+use crate::utils::{cast, to_f64};
+
+use num_traits::Float;
+
+/// Correlation between dims, by index. See `Stack::correlation` for the
+/// matrix of a stack, whose correlations are kept by dim name.
+///
+/// Dims outside the matrix are taken as independent of every other dim.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Correlation<T = f64> {
+    matrix: Vec<Vec<T>>,
+}
+
+impl<T: Float> Correlation<T> {
+    /// Fails unless the matrix is square, symmetric, has a unit diagonal,
+    /// entries in [-1, 1] and is positive semi-definite.
+    pub fn new(matrix: Vec<Vec<T>>) -> Result<Correlation<T>> {
+        let n = matrix.len();
+        for (i, row) in matrix.iter().enumerate() {
+            if row.len() != n {
+                return Err(Error::InvalidCorrelation {
+                    row: i,
+                    col: row.len(),
+                    value: f64::NAN,
+                });
+            }
+            for (j, &value) in row.iter().enumerate() {
+                let valid = if i == j {
+                    value == T::one()
+                } else {
+                    value.abs() <= T::one() && value == matrix[j][i]
+                };
+                if !valid {
+                    return Err(Error::InvalidCorrelation {
+                        row: i,
+                        col: j,
+                        value: to_f64(value),
+                    });
+                }
+            }
+        }
+        let correlation = Correlation { matrix };
+        correlation.cholesky(n)?;
+        Ok(correlation)
+    }
+
+    /// Set the correlation between the dims named `a` and `b` in pairs kept
+    /// by name, checking the pairs against the dims in `names`.
+    pub(crate) fn correlate(
+        correlations: &mut Vec<(String, String, T)>,
+        names: &[&str],
+        a: &str,
+        b: &str,
+        rho: T,
+    ) -> Result<()> {
+        if let Some(name) = [a, b].into_iter().find(|name| !names.contains(name)) {
+            return Err(Error::UnknownDim(name.to_owned()));
+        }
+        let mut updated = correlations.clone();
+        updated.retain(|(x, y, _)| !(x == a && y == b || x == b && y == a));
+        updated.push((a.to_owned(), b.to_owned(), rho));
+        Correlation::by_name(names, &updated)?;
+        *correlations = updated;
+        Ok(())
+    }
+
+    /// Correlation by index of the dims in `names`, from pairs of dim names
+    /// and the correlation between them. Pairs naming a dim not in `names`
+    /// are skipped, and `None` means no pair applies.
+    pub(crate) fn by_name(
+        names: &[&str],
+        pairs: &[(String, String, T)],
+    ) -> Result<Option<Correlation<T>>> {
+        let index = |name: &String| names.iter().position(|n| n == name);
+        let mut matrix = Correlation::identity(names.len()).matrix(names.len());
+        let mut any = false;
+        for (a, b, rho) in pairs {
+            if let (Some(i), Some(j)) = (index(a), index(b)) {
+                matrix[i][j] = *rho;
+                matrix[j][i] = *rho;
+                any = true;
+            }
+        }
+        if !any {
+            return Ok(None);
+        }
+        Correlation::new(matrix).map(Some)
+    }
+
+    /// Independent dims.
+    pub fn identity(n: usize) -> Correlation<T> {
+        Correlation {
+            matrix: (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| if i == j { T::one() } else { T::zero() })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Number of dims the matrix covers.
+    pub fn len(&self) -> usize {
+        self.matrix.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matrix.is_empty()
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> T {
+        if i == j {
+            T::one()
+        } else if i < self.len() && j < self.len() {
+            self.matrix[i][j]
+        } else {
+            T::zero()
+        }
+    }
+
+    /// Set the correlation between dims `i` and `j`, growing the matrix if
+    /// needed.
+    pub fn set(&mut self, i: usize, j: usize, rho: T) -> Result<()> {
+        let n = self.len().max(i + 1).max(j + 1);
+        let mut matrix = self.matrix(n);
+        matrix[i][j] = rho;
+        matrix[j][i] = rho;
+        *self = Correlation::new(matrix)?;
+        Ok(())
+    }
+
+    /// The full matrix for `n` dims.
+    pub fn matrix(&self, n: usize) -> Vec<Vec<T>> {
+        (0..n)
+            .map(|i| (0..n).map(|j| self.get(i, j)).collect())
+            .collect()
+    }
+
+    /// Lower triangular `L` with `L L^T` the matrix for `n` dims, used to
+    /// draw correlated normal samples.
+    pub fn cholesky(&self, n: usize) -> Result<Vec<Vec<T>>> {
+        let a = self.matrix(n);
+        let mut l = vec![vec![T::zero(); n]; n];
+        let tolerance: T = cast(1e-12);
+        for j in 0..n {
+            let d = a[j][j] - (0..j).fold(T::zero(), |acc, k| acc + l[j][k] * l[j][k]);
+            if d < -tolerance {
+                return Err(Error::NotPositiveDefinite);
+            }
+            l[j][j] = d.max(T::zero()).sqrt();
+            for i in j + 1..n {
+                let s = a[i][j] - (0..j).fold(T::zero(), |acc, k| acc + l[i][k] * l[j][k]);
+                l[i][j] = if l[j][j] > tolerance {
+                    s / l[j][j]
+                } else if s.abs() <= tolerance {
+                    T::zero()
+                } else {
+                    return Err(Error::NotPositiveDefinite);
+                };
+            }
+        }
+        Ok(l)
+    }
+}
@@ -1,8 +1,14 @@
+use crate::correlation::Correlation;
 use crate::dist;
+use crate::error::{Error, Result};
+use crate::material::Material;
+use crate::part::Part;
 use crate::stats;
 use crate::tol;
+use crate::utils::{cast, to_f64};
+use crate::validate::{self, Warning};
 
-use std::f32;
+use num_traits::Float;
 
 #[derive(Clone, Copy)]
 pub enum Direction {
@@ -11,14 +17,14 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn signum(&self) -> f32 {
+    pub fn signum<T: Float>(&self) -> T {
         match self {
-            Direction::Positive => 1.0,
-            Direction::Negative => -1.0,
+            Direction::Positive => T::one(),
+            Direction::Negative => -T::one(),
         }
     }
-    pub fn from(num: f32) -> Direction {
-        if num > 0.0 {
+    pub fn from<T: Float>(num: T) -> Direction {
+        if num > T::zero() {
             Direction::Positive
         } else {
             Direction::Negative
@@ -27,26 +33,32 @@ impl Direction {
 }
 
 #[derive(Clone, Copy)]
-pub struct Vec1D {
-    pub x: f32,
+pub struct Vec1D<T = f64> {
+    pub x: T,
 }
 
-impl Vec1D {
-    pub fn new(x: f32) -> Vec1D {
+impl<T: Float> Vec1D<T> {
+    pub fn new(x: T) -> Vec1D<T> {
         Vec1D { x }
     }
 }
 
 #[derive(Clone)]
-pub struct Basic {
-    pub nom: Vec1D,
-    pub tol: tol::Bilateral,
-    pub a: f32, // sensitivity
+pub struct Basic<T = f64> {
+    pub nom: Vec1D<T>,
+    pub tol: tol::Bilateral<T>,
+    pub a: T, // sensitivity
     pub name: String,
     pub desc: String,
+    /// Material the dimension is made of, for thermal analysis.
+    pub material: Option<Material<T>>,
+    /// Part the dimension is on.
+    pub part: Option<Part>,
+    /// Feature of the part the dimension is on, e.g. a bore or face.
+    pub feature: Option<String>,
 }
 
-impl Basic {
+impl<T: Float> Basic<T> {
     /// A Basic geometric 1D measurement.
     /// All measurements are assumed to be 1D linear and relative.
     ///
@@ -58,67 +70,118 @@ impl Basic {
     /// * `name` - Name of the measurement.
     /// * `desc` - Description of the measurement.
     ///
-    pub fn new(nom: Vec1D, tol: tol::Bilateral, a: f32, name: String, desc: String) -> Basic {
+    pub fn new(
+        nom: Vec1D<T>,
+        tol: tol::Bilateral<T>,
+        a: T,
+        name: String,
+        desc: String,
+    ) -> Basic<T> {
         Basic {
             nom,
             tol,
             a,
             name,
             desc,
+            material: None,
+            part: None,
+            feature: None,
         }
     }
 
-    pub fn median(&self) -> f32 {
-        return (self.lower() + self.upper()) / 2.0;
+    pub fn median(&self) -> T {
+        (self.lower() + self.upper()) / cast(2.0)
     }
 
-    pub fn lower(&self) -> f32 {
-        return self.nom.x.abs() + self.tol.lower;
+    pub fn lower(&self) -> T {
+        self.nom.x.abs() + self.tol.lower
     }
 
-    pub fn upper(&self) -> f32 {
-        return self.nom.x.abs() + self.tol.upper;
+    pub fn upper(&self) -> T {
+        self.nom.x.abs() + self.tol.upper
     }
 
-    pub fn absolute_nominal_adj(&self) -> f32 {
-        return self.nom.x * self.a;
+    pub fn absolute_nominal_adj(&self) -> T {
+        self.nom.x * self.a
     }
 
-    pub fn absolute_median(&self) -> f32 {
-        return (self.absolute_lower() + self.absolute_upper()) / 2.0;
+    pub fn absolute_median(&self) -> T {
+        (self.absolute_lower() + self.absolute_upper()) / cast(2.0)
     }
 
-    pub fn absolute_median_adj(&self) -> f32 {
-        return self.absolute_median() * self.a;
+    pub fn absolute_median_adj(&self) -> T {
+        self.absolute_median() * self.a
     }
 
-    pub fn absolute_lower(&self) -> f32 {
-        return self.nom.x + self.absolute_lower_tol();
+    pub fn absolute_lower(&self) -> T {
+        self.nom.x + self.absolute_lower_tol()
     }
 
-    pub fn absolute_upper(&self) -> f32 {
-        return self.nom.x + self.absolute_upper_tol();
+    pub fn absolute_upper(&self) -> T {
+        self.nom.x + self.absolute_upper_tol()
     }
 
-    pub fn absolute_lower_tol(&self) -> f32 {
-        if self.nom.x > 0.0 {
-            return self.tol.lower;
+    pub fn absolute_lower_tol(&self) -> T {
+        if self.nom.x > T::zero() {
+            self.tol.lower
         } else {
-            return -self.tol.upper;
+            -self.tol.upper
         }
     }
 
-    pub fn absolute_upper_tol(&self) -> f32 {
-        if self.nom.x > 0.0 {
-            return self.tol.upper;
+    pub fn absolute_upper_tol(&self) -> T {
+        if self.nom.x > T::zero() {
+            self.tol.upper
         } else {
-            return -self.tol.lower;
+            -self.tol.lower
+        }
+    }
+
+    /// Start building a Basic dimension.
+    ///
+    /// Defaults to a nominal of zero, no tolerance and a sensitivity of one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use dimstack_rs::dim::Basic;
+    /// let shaft = Basic::builder("Shaft").nominal(208.0).sym(0.036).build();
+    /// assert_eq!(shaft.upper(), 208.036);
+    /// ```
+    pub fn builder(name: &str) -> BasicBuilder<T> {
+        BasicBuilder {
+            basic: Basic::new(
+                Vec1D::new(T::zero()),
+                tol::Bilateral::symmetric(T::zero()),
+                T::one(),
+                name.to_owned(),
+                "".to_owned(),
+            ),
         }
     }
 
-    pub fn recenter_nom(&self) -> Basic {
+    /// Build a Basic from absolute limits around a nominal.
+    ///
+    /// Takes care of the tolerance being relative to the absolute value of a
+    /// negative nominal.
+    pub fn from_limits(name: &str, nom: T, lower: T, upper: T) -> Basic<T> {
+        let tol = if nom > T::zero() {
+            tol::Bilateral::unequal(upper - nom, lower - nom)
+        } else {
+            tol::Bilateral::unequal(nom - lower, nom - upper)
+        };
+        Basic::new(
+            Vec1D::new(nom),
+            tol,
+            T::one(),
+            name.to_owned(),
+            "".to_owned(),
+        )
+    }
+
+    pub fn recenter_nom(&self) -> Basic<T> {
         let median = self.median();
-        let tol = self.tol.t() / 2.0;
+        let tol = self.tol.t() / cast(2.0);
 
         Basic::new(
             Vec1D::new(median),
@@ -136,53 +199,93 @@ impl Basic {
     // }
 }
 
-pub struct Stack {
-    pub name: String,
-    pub description: String,
-    pub dims: Vec<Basic>,
+pub struct BasicBuilder<T = f64> {
+    basic: Basic<T>,
 }
 
-impl Stack {
-    pub fn new(name: String, description: String, dims: Vec<Basic>) -> Stack {
-        Stack {
-            name,
-            description,
-            dims,
-        }
+impl<T: Float> BasicBuilder<T> {
+    pub fn nominal(mut self, nom: T) -> BasicBuilder<T> {
+        self.basic.nom = Vec1D::new(nom);
+        self
     }
 
-    pub fn append(&mut self, measurement: Basic) {
-        self.dims.push(measurement);
+    /// Symmetric tolerance, see `tol::Bilateral::symmetric`.
+    pub fn sym(mut self, tol: T) -> BasicBuilder<T> {
+        self.basic.tol = tol::Bilateral::symmetric(tol);
+        self
     }
 
-    pub fn from_reviewed(reviewed_stack: &ReviewedStack) -> Stack {
-        let dims = reviewed_stack
-            .dims
-            .iter()
-            .map(|rdim| rdim.dim.clone())
-            .collect();
-        Stack {
-            name: reviewed_stack.name.clone(),
-            description: reviewed_stack.description.clone(),
-            dims,
+    /// Unequal tolerance, see `tol::Bilateral::unequal`.
+    pub fn unequal(mut self, upper: T, lower: T) -> BasicBuilder<T> {
+        self.basic.tol = tol::Bilateral::unequal(upper, lower);
+        self
+    }
+
+    pub fn tol(mut self, tol: tol::Bilateral<T>) -> BasicBuilder<T> {
+        self.basic.tol = tol;
+        self
+    }
+
+    pub fn sensitivity(mut self, a: T) -> BasicBuilder<T> {
+        self.basic.a = a;
+        self
+    }
+
+    pub fn desc(mut self, desc: &str) -> BasicBuilder<T> {
+        self.basic.desc = desc.to_owned();
+        self
+    }
+
+    pub fn material(mut self, material: Material<T>) -> BasicBuilder<T> {
+        self.basic.material = Some(material);
+        self
+    }
+
+    pub fn part(mut self, part: Part) -> BasicBuilder<T> {
+        self.basic.part = Some(part);
+        self
+    }
+
+    pub fn feature(mut self, feature: &str) -> BasicBuilder<T> {
+        self.basic.feature = Some(feature.to_owned());
+        self
+    }
+
+    /// Continue with process data for the dimension.
+    pub fn reviewed(self, target_process_sigma: T) -> ReviewedBuilder<T> {
+        ReviewedBuilder {
+            dim: self.build(),
+            target_process_sigma,
+            skew: None,
+            distribution: None,
         }
     }
+
+    pub fn build(self) -> Basic<T> {
+        self.basic
+    }
+}
+
+impl<T: Float> From<BasicBuilder<T>> for Basic<T> {
+    fn from(builder: BasicBuilder<T>) -> Basic<T> {
+        builder.build()
+    }
 }
 
 #[derive(Clone)]
-pub struct Reviewed {
-    pub dim: Basic,
-    pub target_process_sigma: f32,
+pub struct Reviewed<T = f64> {
+    pub dim: Basic<T>,
+    pub target_process_sigma: T,
     // TODO: remove option
-    pub distribution: Option<dist::Distribution>,
+    pub distribution: Option<dist::Distribution<T>>,
 }
 
-impl Reviewed {
+impl<T: Float> Reviewed<T> {
     pub fn new(
-        dim: Basic,
-        target_process_sigma: f32,
-        distribution: Option<dist::Distribution>,
-    ) -> Reviewed {
+        dim: Basic<T>,
+        target_process_sigma: T,
+        distribution: Option<dist::Distribution<T>>,
+    ) -> Result<Reviewed<T>> {
         let mut ret = Reviewed {
             dim,
             target_process_sigma,
@@ -190,12 +293,12 @@ impl Reviewed {
         };
         // if distribution is none, assume normal distribution
         if ret.distribution.is_none() {
-            ret = ret.assume_normal_dist();
+            ret = ret.assume_normal_dist()?;
         }
-        return ret;
+        Ok(ret)
     }
 
-    pub fn of_basic(basic: Basic, target_process_sigma: f32) -> Reviewed {
+    pub fn of_basic(basic: Basic<T>, target_process_sigma: T) -> Result<Reviewed<T>> {
         Reviewed::new(basic, target_process_sigma, None)
     }
 
@@ -208,16 +311,20 @@ impl Reviewed {
     //     self
     // }
 
-    pub fn assume_normal_dist(self) -> Reviewed {
+    pub fn assume_normal_dist(self) -> Result<Reviewed<T>> {
+        let sigma = self.target_process_sigma;
+        if !(sigma > T::zero() && sigma.is_finite()) {
+            return Err(Error::InvalidProcessSigma(to_f64(sigma)));
+        }
         let mean = self.mean_eff();
-        let std_dev = (self.dim.absolute_upper() - self.dim.absolute_lower())
-            / (2.0 * self.target_process_sigma);
-        let distribution = dist::Normal::new(mean, std_dev);
-        Reviewed {
+        let std_dev =
+            (self.dim.absolute_upper() - self.dim.absolute_lower()) / (cast::<T>(2.0) * sigma);
+        let distribution = dist::Normal::new(mean, std_dev)?;
+        Ok(Reviewed {
             dim: self.dim,
-            target_process_sigma: self.target_process_sigma,
+            target_process_sigma: sigma,
             distribution: Some(dist::Distribution::Normal(distribution)),
-        }
+        })
     }
 
     // pub fn assume_normal_dist_skewed(&mut self, skew: f32) -> &mut Reviewed {
@@ -233,33 +340,33 @@ impl Reviewed {
     //     return self;
     // }
 
-    pub fn assume_normal_dist_skewed(self, skew: f32) -> Reviewed {
-        let reviewed = self.assume_normal_dist();
+    pub fn assume_normal_dist_skewed(self, skew: T) -> Result<Reviewed<T>> {
+        let reviewed = self.assume_normal_dist()?;
         match &reviewed.distribution {
             Some(dist::Distribution::Normal(normal)) => {
                 let mean = normal.mean + skew * (normal.std_dev * reviewed.target_process_sigma);
-                let distribution = dist::Normal::new(mean, normal.std_dev);
-                Reviewed {
+                let distribution = dist::Normal::new(mean, normal.std_dev)?;
+                Ok(Reviewed {
                     dim: reviewed.dim,
                     target_process_sigma: reviewed.target_process_sigma,
                     distribution: Some(dist::Distribution::Normal(distribution)),
-                }
+                })
             }
-            _ => reviewed,
+            _ => Ok(reviewed),
         }
     }
 
-    pub fn c_p(&self) -> f32 {
+    pub fn c_p(&self) -> T {
         // only normal distribution is supported
         match &self.distribution {
             Some(dist::Distribution::Normal(normal)) => {
                 stats::c_p(self.dim.upper(), self.dim.lower(), normal.std_dev)
             }
-            _ => 0.0,
+            _ => T::zero(),
         }
     }
 
-    pub fn c_pk(&self) -> f32 {
+    pub fn c_pk(&self) -> T {
         // only normal distribution is supported
         match &self.distribution {
             Some(dist::Distribution::Normal(normal)) => stats::c_pk(
@@ -268,135 +375,318 @@ impl Reviewed {
                 normal.mean,
                 normal.std_dev,
             ),
-            _ => 0.0,
+            _ => T::zero(),
         }
     }
 
-    pub fn mean_eff(&self) -> f32 {
-        (self.dim.absolute_lower() + self.dim.absolute_upper()) / 2.0
+    pub fn mean_eff(&self) -> T {
+        (self.dim.absolute_lower() + self.dim.absolute_upper()) / cast(2.0)
     }
 
     /// Effective standard deviation
     /// https://www.six-sigma-material.com/
     /// "6 std_dev" is the standard deviation of the distribution
     ///
+    /// The standard deviation of any distribution type, inflated by `k` for
+    /// a mean off center.
+    ///
     /// TODO: document source
-    pub fn std_dev_eff(&self) -> f32 {
+    pub fn std_dev_eff(&self) -> T {
         match &self.distribution {
-            Some(dist::Distribution::Normal(normal)) => {
-                let outer_shift = f32::min(
-                    self.dim.absolute_upper() - normal.mean,
-                    normal.mean - self.dim.absolute_lower(),
-                );
-                return (self.dim.tol.t() * normal.std_dev) / (2.0 * outer_shift);
-            }
-            _ => 0.0,
+            Some(distribution) => self.k() * distribution.std_dev(),
+            None => T::zero(),
         }
     }
 
-    pub fn process_sigma_eff(&self) -> f32 {
-        if self.std_dev_eff() == 0.0 {
-            return 0.0;
+    pub fn process_sigma_eff(&self) -> T {
+        if self.std_dev_eff() == T::zero() {
+            return T::zero();
         }
         let min_tol_gap = (self.dim.absolute_upper() - self.mean_eff())
             .min(self.mean_eff() - self.dim.absolute_lower());
         min_tol_gap / self.std_dev_eff()
     }
 
-    pub fn k(&self) -> f32 {
+    /// Factor the standard deviation is inflated by for a mean off center,
+    /// the half tolerance over the distance from the mean to the nearest
+    /// limit. For a mean shifted by a fraction `k` of the half tolerance,
+    /// as in MITCalc, this is `1 / (1 - k)`.
+    pub fn k(&self) -> T {
         match &self.distribution {
-            Some(dist::Distribution::Normal(normal)) => {
-                let outer_shift = f32::min(
-                    self.dim.absolute_upper() - normal.mean,
-                    normal.mean - self.dim.absolute_lower(),
+            Some(distribution) => {
+                let mean = distribution.mean();
+                let outer_shift = T::min(
+                    self.dim.absolute_upper() - mean,
+                    mean - self.dim.absolute_lower(),
                 );
-                return self.dim.tol.t() / (2.0 * outer_shift);
+                self.dim.tol.t() / (cast::<T>(2.0) * outer_shift)
             }
-            _ => 0.0,
+            None => T::zero(),
         }
     }
 
-    pub fn yield_loss_probability(&self) -> f32 {
-        if self.distribution.is_none() {
-            return 0.0;
+    /// Probability of falling outside the limits, for any distribution.
+    ///
+    /// Computed from the two tails directly, as `Requirement` does.
+    pub fn yield_loss_probability(&self) -> T {
+        match &self.distribution {
+            Some(distribution) => {
+                distribution.cdf(self.dim.absolute_lower())
+                    + distribution.sf(self.dim.absolute_upper())
+            }
+            None => T::zero(),
         }
-        1.0 - self.yield_probability()
     }
 
-    pub fn yield_probability(&self) -> f32 {
-        // only normal distribution is supported
-        match &self.distribution {
-            Some(dist::Distribution::Normal(normal)) => {
-                let ul = self.dim.absolute_upper();
-                let ll = self.dim.absolute_lower();
-                normal.cdf(ul) - normal.cdf(ll)
+    pub fn yield_probability(&self) -> T {
+        T::one() - self.yield_loss_probability()
+    }
+}
+
+pub struct ReviewedBuilder<T = f64> {
+    dim: Basic<T>,
+    target_process_sigma: T,
+    skew: Option<T>,
+    distribution: Option<dist::Distribution<T>>,
+}
+
+impl<T: Float> ReviewedBuilder<T> {
+    /// Shift the assumed normal distribution, see `Reviewed::assume_normal_dist_skewed`.
+    pub fn skew(mut self, skew: T) -> ReviewedBuilder<T> {
+        self.skew = Some(skew);
+        self
+    }
+
+    /// Use a known distribution instead of assuming a normal one.
+    pub fn distribution(mut self, distribution: dist::Distribution<T>) -> ReviewedBuilder<T> {
+        self.distribution = Some(distribution);
+        self
+    }
+
+    pub fn build(self) -> Result<Reviewed<T>> {
+        match (self.distribution, self.skew) {
+            (Some(distribution), _) => {
+                Reviewed::new(self.dim, self.target_process_sigma, Some(distribution))
             }
-            _ => 0.0,
+            (None, Some(skew)) => Reviewed::of_basic(self.dim, self.target_process_sigma)?
+                .assume_normal_dist_skewed(skew),
+            (None, None) => Reviewed::of_basic(self.dim, self.target_process_sigma),
+        }
+    }
+}
+/// An entry in a `Stack`: the design dimension, with process data when it
+/// has been reviewed.
+#[derive(Clone)]
+pub enum Contributor<T = f64> {
+    Basic(Basic<T>),
+    Reviewed(Reviewed<T>),
+}
+
+impl<T: Float> Contributor<T> {
+    /// The design dimension.
+    pub fn basic(&self) -> &Basic<T> {
+        match self {
+            Contributor::Basic(basic) => basic,
+            Contributor::Reviewed(reviewed) => &reviewed.dim,
+        }
+    }
+
+    pub fn basic_mut(&mut self) -> &mut Basic<T> {
+        match self {
+            Contributor::Basic(basic) => basic,
+            Contributor::Reviewed(reviewed) => &mut reviewed.dim,
+        }
+    }
+
+    /// The process data, if the dimension has been reviewed.
+    pub fn reviewed(&self) -> Option<&Reviewed<T>> {
+        match self {
+            Contributor::Basic(_) => None,
+            Contributor::Reviewed(reviewed) => Some(reviewed),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.basic().name
+    }
+
+    /// Distribution of the absolute value of the dimension.
+    ///
+    /// Dims without process data are assumed normal, centered in the
+    /// tolerance band with the band at +/- 3 sigma, as `calc::rss` does.
+    /// Returns `None` for a dim with no tolerance, which is a constant.
+    pub fn distribution(&self) -> Option<dist::Distribution<T>> {
+        if let Some(distribution) = self.reviewed().and_then(|r| r.distribution.clone()) {
+            return Some(distribution);
         }
+        let basic = self.basic();
+        let std_dev = basic.tol.t() / cast(6.0);
+        dist::Normal::new(basic.absolute_median(), std_dev)
+            .ok()
+            .map(dist::Distribution::Normal)
+    }
+}
+
+impl<T: Float> From<Basic<T>> for Contributor<T> {
+    fn from(basic: Basic<T>) -> Contributor<T> {
+        Contributor::Basic(basic)
+    }
+}
+
+impl<T: Float> From<BasicBuilder<T>> for Contributor<T> {
+    fn from(builder: BasicBuilder<T>) -> Contributor<T> {
+        Contributor::Basic(builder.build())
+    }
+}
+
+impl<T: Float> From<Reviewed<T>> for Contributor<T> {
+    fn from(reviewed: Reviewed<T>) -> Contributor<T> {
+        Contributor::Reviewed(reviewed)
     }
 }
-pub struct ReviewedStack {
+
+/// A stack of dims, each with its design tolerance and optional process
+/// data, so that every analysis in `calc` can run on the same stack.
+#[derive(Clone)]
+pub struct Stack<T = f64> {
     pub name: String,
     pub description: String,
-    pub dims: Vec<Reviewed>,
+    pub dims: Vec<Contributor<T>>,
+    /// Correlations between dims by name, see `correlate`.
+    pub correlations: Vec<(String, String, T)>,
 }
 
-impl ReviewedStack {
-    pub fn new(name: String, description: String, dims: Vec<Reviewed>) -> ReviewedStack {
-        ReviewedStack {
+impl<T: Float> Stack<T> {
+    pub fn new(name: String, description: String, dims: Vec<Contributor<T>>) -> Stack<T> {
+        Stack {
             name,
             description,
             dims,
+            correlations: Vec::new(),
+        }
+    }
+
+    pub fn builder(name: &str) -> StackBuilder<T> {
+        StackBuilder {
+            stack: Stack::new(name.to_owned(), "".to_owned(), Vec::new()),
         }
     }
 
-    pub fn append(&mut self, measurement: Reviewed) {
-        self.dims.push(measurement);
+    /// Add a dim, either a `Basic` or a `Reviewed`.
+    pub fn append(&mut self, measurement: impl Into<Contributor<T>>) {
+        self.dims.push(measurement.into());
+    }
+
+    /// Correlate the dims named `a` and `b`, e.g. two features cut in the
+    /// same setup. The correlation is between the sizes of the dims, so it
+    /// holds whatever their directions in the stack.
+    pub fn correlate(&mut self, a: &str, b: &str, rho: T) -> Result<()> {
+        let names: Vec<&str> = self.dims.iter().map(|dim| dim.name()).collect();
+        Correlation::correlate(&mut self.correlations, &names, a, b, rho)
+    }
+
+    /// Correlation matrix by the current index of each dim, `None` when the
+    /// dims are independent. Pairs naming a dim no longer in the stack are
+    /// dropped, which `validate` warns of. Fails if the correlations have been
+    /// edited into an invalid matrix.
+    pub fn correlation(&self) -> Result<Option<Correlation<T>>> {
+        Correlation::by_name(&self.names(), &self.correlations)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.dims.iter().map(|dim| dim.name()).collect()
+    }
+
+    /// The design dims of every contributor.
+    pub fn basics(&self) -> impl Iterator<Item = &Basic<T>> {
+        self.dims.iter().map(|dim| dim.basic())
+    }
+
+    /// Lint the stack for common mistakes, including process data that
+    /// contradicts the design limits. See `validate::Warning`.
+    pub fn validate(&self) -> Vec<Warning> {
+        let basics: Vec<Basic<T>> = self.basics().cloned().collect();
+        let reviewed: Vec<&Reviewed<T>> = self.dims.iter().filter_map(|d| d.reviewed()).collect();
+        let mut warnings = validate::check_dims(&basics);
+        warnings.extend(validate::check_reviewed(&reviewed));
+        warnings.extend(validate::check_correlations(
+            &self.names(),
+            &self.correlations,
+        ));
+        warnings
+    }
+}
+
+pub struct StackBuilder<T = f64> {
+    stack: Stack<T>,
+}
+
+impl<T: Float> StackBuilder<T> {
+    pub fn desc(mut self, description: &str) -> StackBuilder<T> {
+        self.stack.description = description.to_owned();
+        self
+    }
+
+    /// Add a dim, either a `Basic`, an unfinished `BasicBuilder` or a `Reviewed`.
+    pub fn push(mut self, dim: impl Into<Contributor<T>>) -> StackBuilder<T> {
+        self.stack.append(dim);
+        self
+    }
+
+    pub fn build(self) -> Stack<T> {
+        self.stack
     }
 }
 
-pub struct Requirement {
+pub struct Requirement<T = f64> {
     pub name: String,
     pub description: String,
-    pub distribution: dist::Distribution,
-    pub ll: f32,
-    pub ul: f32,
+    pub distribution: dist::Distribution<T>,
+    pub ll: T,
+    pub ul: T,
 }
 
-impl Requirement {
+impl<T: Float> Requirement<T> {
     pub fn new(
         name: String,
         description: String,
-        distribution: dist::Distribution,
-        ll: f32,
-        ul: f32,
-    ) -> Requirement {
-        Requirement {
+        distribution: dist::Distribution<T>,
+        ll: T,
+        ul: T,
+    ) -> Result<Requirement<T>> {
+        if ll > ul {
+            return Err(Error::InvertedLimits {
+                lower: to_f64(ll),
+                upper: to_f64(ul),
+            });
+        }
+        Ok(Requirement {
             name,
             description,
             distribution,
             ll,
             ul,
-        }
+        })
     }
 
-    pub fn median(&self) -> f32 {
-        (self.ll + self.ul) / 2.0
+    pub fn median(&self) -> T {
+        (self.ll + self.ul) / cast(2.0)
     }
 
-    pub fn yield_loss_probability(&self) -> f32 {
-        1.0 - self.yield_probability()
+    /// Probability of falling outside the limits.
+    ///
+    /// Computed from the two tails directly rather than as
+    /// `1 - yield_probability()`, which cancels out at PPM levels.
+    pub fn yield_loss_probability(&self) -> T {
+        self.distribution.cdf(self.ll) + self.distribution.sf(self.ul)
     }
 
-    pub fn yield_probability(&self) -> f32 {
-        match &self.distribution {
-            dist::Distribution::Uniform(uniform) => uniform.cdf(self.ul) - uniform.cdf(self.ll),
-            dist::Distribution::Normal(normal) => normal.cdf(self.ul) - normal.cdf(self.ll),
-        }
+    pub fn yield_probability(&self) -> T {
+        self.distribution.cdf(self.ul) - self.distribution.cdf(self.ll)
     }
 
-    pub fn r(&self) -> f32 {
-        self.yield_loss_probability() * 1000000.0
+    pub fn r(&self) -> T {
+        self.yield_loss_probability() * cast(1000000.0)
     }
 
     // pub fn dict(&self) -> Vec<serde_json::Value> {
@@ -1,75 +1,646 @@
-use statrs::function::erf::erf;
-use std::f32::consts::PI;
+use crate::error::{Error, Result};
+use crate::utils::{cast, to_f64};
+use num_traits::Float;
+use statrs::function::erf::{erf_inv, erfc};
+use std::f64::consts::PI;
 
 // TODO: https://docs.rs/statrs/latest/statrs/distribution/index.html
 #[derive(Clone)]
-pub enum Distribution {
-    Uniform(Uniform),
-    Normal(Normal),
+pub enum Distribution<T = f64> {
+    Uniform(Uniform<T>),
+    Normal(Normal<T>),
+    Empirical(Empirical<T>),
+    Tabulated(Tabulated<T>),
 }
+
+impl<T: Float> Distribution<T> {
+    pub fn mean(&self) -> T {
+        match self {
+            Distribution::Uniform(uniform) => uniform.mean(),
+            Distribution::Normal(normal) => normal.mean,
+            Distribution::Empirical(empirical) => empirical.mean(),
+            Distribution::Tabulated(tabulated) => tabulated.mean(),
+        }
+    }
+
+    pub fn variance(&self) -> T {
+        match self {
+            Distribution::Uniform(uniform) => uniform.variance(),
+            Distribution::Normal(normal) => normal.variance(),
+            Distribution::Empirical(empirical) => empirical.variance(),
+            Distribution::Tabulated(tabulated) => tabulated.variance(),
+        }
+    }
+
+    pub fn std_dev(&self) -> T {
+        self.variance().sqrt()
+    }
+
+    /// Distribution of `k * X` for a nonzero `k`.
+    pub fn scaled(&self, k: T) -> Distribution<T> {
+        // a negative k reverses the order of everything kept sorted
+        let ordered = |mut v: Vec<T>| {
+            if k < T::zero() {
+                v.reverse();
+            }
+            v
+        };
+        match self {
+            Distribution::Uniform(uniform) => Distribution::Uniform(Uniform {
+                lower: (uniform.lower * k).min(uniform.upper * k),
+                upper: (uniform.lower * k).max(uniform.upper * k),
+            }),
+            Distribution::Normal(normal) => Distribution::Normal(Normal {
+                mean: normal.mean * k,
+                std_dev: normal.std_dev * k.abs(),
+            }),
+            Distribution::Empirical(empirical) => Distribution::Empirical(Empirical {
+                samples: ordered(empirical.samples.iter().map(|&x| x * k).collect()),
+            }),
+            Distribution::Tabulated(tabulated) => {
+                let (below, above) = if k < T::zero() {
+                    (&tabulated.above, &tabulated.below)
+                } else {
+                    (&tabulated.below, &tabulated.above)
+                };
+                Distribution::Tabulated(Tabulated {
+                    x: ordered(tabulated.x.iter().map(|&x| x * k).collect()),
+                    pdf: ordered(tabulated.pdf.iter().map(|&p| p / k.abs()).collect()),
+                    below: ordered(below.clone()),
+                    above: ordered(above.clone()),
+                })
+            }
+        }
+    }
+
+    /// Distribution of `X + d`.
+    pub fn shifted(&self, d: T) -> Distribution<T> {
+        match self {
+            Distribution::Uniform(uniform) => Distribution::Uniform(Uniform {
+                lower: uniform.lower + d,
+                upper: uniform.upper + d,
+            }),
+            Distribution::Normal(normal) => Distribution::Normal(Normal {
+                mean: normal.mean + d,
+                std_dev: normal.std_dev,
+            }),
+            Distribution::Empirical(empirical) => Distribution::Empirical(Empirical {
+                samples: empirical.samples.iter().map(|&x| x + d).collect(),
+            }),
+            Distribution::Tabulated(tabulated) => Distribution::Tabulated(Tabulated {
+                x: tabulated.x.iter().map(|&x| x + d).collect(),
+                ..tabulated.clone()
+            }),
+        }
+    }
+
+    /// Interval holding the distribution, cut at +/- 10 sigma for a normal
+    /// distribution, which leaves out less than 1e-22 of it.
+    pub fn support(&self) -> (T, T) {
+        match self {
+            Distribution::Uniform(uniform) => (uniform.lower, uniform.upper),
+            Distribution::Normal(normal) => {
+                let spread = normal.std_dev * cast(10.0);
+                (normal.mean - spread, normal.mean + spread)
+            }
+            Distribution::Empirical(empirical) => (
+                empirical.samples[0],
+                empirical.samples[empirical.samples.len() - 1],
+            ),
+            Distribution::Tabulated(tabulated) => {
+                (tabulated.x[0], tabulated.x[tabulated.x.len() - 1])
+            }
+        }
+    }
+
+    /// `E[(X - mean)^k]`.
+    pub fn central_moment(&self, k: i32) -> T {
+        match self {
+            Distribution::Uniform(uniform) => uniform.central_moment(k),
+            Distribution::Normal(normal) => normal.central_moment(k),
+            Distribution::Empirical(empirical) => empirical.central_moment(k),
+            Distribution::Tabulated(tabulated) => tabulated.central_moment(k),
+        }
+    }
+
+    pub fn skewness(&self) -> T {
+        self.central_moment(3) / self.variance().powf(cast(1.5))
+    }
+
+    /// Kurtosis, 3 for a normal distribution.
+    pub fn kurtosis(&self) -> T {
+        self.central_moment(4) / self.variance().powi(2)
+    }
+
+    pub fn moments(&self) -> Moments<T> {
+        Moments {
+            mean: self.mean(),
+            variance: self.variance(),
+            skewness: self.skewness(),
+            kurtosis: self.kurtosis(),
+        }
+    }
+
+    pub fn cdf(&self, x: T) -> T {
+        match self {
+            Distribution::Uniform(uniform) => uniform.cdf(x),
+            Distribution::Normal(normal) => normal.cdf(x),
+            Distribution::Empirical(empirical) => empirical.cdf(x),
+            Distribution::Tabulated(tabulated) => tabulated.cdf(x),
+        }
+    }
+
+    /// Survival function, `1 - cdf(x)`.
+    pub fn sf(&self, x: T) -> T {
+        match self {
+            Distribution::Uniform(uniform) => uniform.sf(x),
+            Distribution::Normal(normal) => normal.sf(x),
+            Distribution::Empirical(empirical) => empirical.sf(x),
+            Distribution::Tabulated(tabulated) => tabulated.sf(x),
+        }
+    }
+
+    /// Inverse of the cdf, used to draw samples from a uniform `p`.
+    pub fn quantile(&self, p: T) -> T {
+        match self {
+            Distribution::Uniform(uniform) => uniform.quantile(p),
+            Distribution::Normal(normal) => normal.quantile(p),
+            Distribution::Empirical(empirical) => empirical.quantile(p),
+            Distribution::Tabulated(tabulated) => tabulated.quantile(p),
+        }
+    }
+}
+
 #[derive(Clone)]
-pub struct Uniform {
-    pub upper: f32,
-    pub lower: f32,
+pub struct Uniform<T = f64> {
+    pub upper: T,
+    pub lower: T,
 }
 
-impl Uniform {
-    pub fn new(lower: f32, upper: f32) -> Uniform {
-        Uniform { lower, upper }
+impl<T: Float> Uniform<T> {
+    pub fn new(lower: T, upper: T) -> Result<Uniform<T>> {
+        if lower.is_nan() || upper.is_nan() || lower >= upper {
+            return Err(Error::InvertedLimits {
+                lower: to_f64(lower),
+                upper: to_f64(upper),
+            });
+        }
+        Ok(Uniform { lower, upper })
+    }
+
+    pub fn mean(&self) -> T {
+        (self.lower + self.upper) / cast(2.0)
     }
 
-    pub fn pdf(&self, x: f32) -> f32 {
+    pub fn variance(&self) -> T {
+        let t = self.upper - self.lower;
+        t * t / cast(12.0)
+    }
+
+    pub fn central_moment(&self, k: i32) -> T {
+        if k % 2 == 1 {
+            return T::zero();
+        }
+        let half = (self.upper - self.lower) / cast(2.0);
+        half.powi(k) / cast(f64::from(k + 1))
+    }
+
+    pub fn pdf(&self, x: T) -> T {
         if x < self.lower || x > self.upper {
-            return 0.0;
+            return T::zero();
+        }
+        T::one() / (self.upper - self.lower)
+    }
+
+    pub fn cdf(&self, x: T) -> T {
+        if x < self.lower {
+            return T::zero();
+        } else if x > self.upper {
+            return T::one();
         }
-        return 1.0 / (self.upper - self.lower);
+        (x - self.lower) / (self.upper - self.lower)
     }
 
-    pub fn cdf(&self, x: f32) -> f32 {
+    /// Survival function, `1 - cdf(x)`.
+    pub fn sf(&self, x: T) -> T {
         if x < self.lower {
-            return 0.0;
+            return T::one();
         } else if x > self.upper {
-            return 1.0;
+            return T::zero();
         }
-        return (x - self.lower) / (self.upper - self.lower);
+        (self.upper - x) / (self.upper - self.lower)
+    }
+
+    pub fn quantile(&self, p: T) -> T {
+        self.lower + p * (self.upper - self.lower)
     }
 }
 // https://docs.rs/statrs/latest/statrs/distribution/struct.Normal.html
 #[derive(Clone)]
-pub struct Normal {
-    pub mean: f32,
-    pub std_dev: f32,
+pub struct Normal<T = f64> {
+    pub mean: T,
+    pub std_dev: T,
 }
 
-impl Normal {
-    pub fn new(mean: f32, std_dev: f32) -> Normal {
-        Normal { mean, std_dev }
+impl<T: Float> Normal<T> {
+    pub fn new(mean: T, std_dev: T) -> Result<Normal<T>> {
+        if !(std_dev > T::zero() && std_dev.is_finite()) {
+            return Err(Error::InvalidStdDev(to_f64(std_dev)));
+        }
+        Ok(Normal { mean, std_dev })
+    }
+
+    pub fn variance(&self) -> T {
+        self.std_dev * self.std_dev
     }
 
-    pub fn variance(&self) -> f32 {
-        return self.std_dev * self.std_dev;
+    pub fn central_moment(&self, k: i32) -> T {
+        if k % 2 == 1 {
+            return T::zero();
+        }
+        // (k - 1)!!
+        let double_factorial = (1..k).step_by(2).fold(1.0, |acc, i| acc * f64::from(i));
+        self.std_dev.powi(k) * cast(double_factorial)
     }
 
     /// https://people.sc.fsu.edu/~jburkardt/c_src/prob/prob.c
-    pub fn pdf(&self, x: f32) -> f32 {
-        return 1.0 / (self.std_dev * (2.0 * PI).sqrt())
-            * (-0.5 * f32::powf((x - self.mean) / self.std_dev, 2.0)).exp();
+    pub fn pdf(&self, x: T) -> T {
+        let z = (x - self.mean) / self.std_dev;
+        T::one() / (self.std_dev * cast::<T>(2.0 * PI).sqrt()) * (cast::<T>(-0.5) * z * z).exp()
+    }
+
+    pub fn cdf(&self, x: T) -> T {
+        cast(0.5 * erfc(-self.z(x) / 2f64.sqrt()))
     }
 
-    pub fn cdf(&self, x: f32) -> f32 {
-        return 0.5
-            * (1.0 + erf(((x - self.mean) / (self.std_dev * f32::sqrt(2.0))) as f64)) as f32;
+    /// Survival function, `1 - cdf(x)`.
+    ///
+    /// Evaluated with `erfc` so that tail probabilities in the PPM range
+    /// are not lost to cancellation.
+    pub fn sf(&self, x: T) -> T {
+        cast(0.5 * erfc(self.z(x) / 2f64.sqrt()))
     }
 
-    pub fn fit(measurements: &Vec<f32>) -> Normal {
-        let mean = measurements.iter().sum::<f32>() / measurements.len() as f32;
+    pub fn quantile(&self, p: T) -> T {
+        let z = 2f64.sqrt() * erf_inv(2.0 * to_f64(p) - 1.0);
+        self.mean + self.std_dev * cast(z)
+    }
+
+    pub fn fit(measurements: &[T]) -> Result<Normal<T>> {
+        if measurements.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+        let n = T::from(measurements.len()).unwrap();
+        let mean = measurements.iter().fold(T::zero(), |acc, &x| acc + x) / n;
         let variance = measurements
             .iter()
-            .map(|x| f32::powf(x - mean, 2.0))
-            .sum::<f32>()
-            / measurements.len() as f32;
+            .fold(T::zero(), |acc, &x| acc + (x - mean) * (x - mean))
+            / n;
         let std_dev = variance.sqrt();
-        return Normal { mean, std_dev };
+        Normal::new(mean, std_dev)
+    }
+
+    fn z(&self, x: T) -> f64 {
+        ((x - self.mean) / self.std_dev).to_f64().unwrap()
+    }
+}
+
+/// Distribution of the length of a vector whose two components are
+/// independent normals with standard deviation `sigma`, e.g. the radial
+/// error of a position tolerance.
+#[derive(Clone)]
+pub struct Rayleigh<T = f64> {
+    pub sigma: T,
+}
+
+impl<T: Float> Rayleigh<T> {
+    pub fn new(sigma: T) -> Result<Rayleigh<T>> {
+        if !(sigma > T::zero() && sigma.is_finite()) {
+            return Err(Error::InvalidStdDev(to_f64(sigma)));
+        }
+        Ok(Rayleigh { sigma })
+    }
+
+    pub fn mean(&self) -> T {
+        self.sigma * cast::<T>(PI / 2.0).sqrt()
+    }
+
+    pub fn variance(&self) -> T {
+        self.sigma * self.sigma * cast(2.0 - PI / 2.0)
+    }
+
+    pub fn pdf(&self, x: T) -> T {
+        if x < T::zero() {
+            return T::zero();
+        }
+        let s2 = self.sigma * self.sigma;
+        x / s2 * (-x * x / (cast::<T>(2.0) * s2)).exp()
+    }
+
+    pub fn cdf(&self, x: T) -> T {
+        T::one() - self.sf(x)
+    }
+
+    /// Survival function, `1 - cdf(x)`.
+    pub fn sf(&self, x: T) -> T {
+        if x < T::zero() {
+            return T::one();
+        }
+        (-x * x / (cast::<T>(2.0) * self.sigma * self.sigma)).exp()
+    }
+
+    pub fn quantile(&self, p: T) -> T {
+        self.sigma * (cast::<T>(-2.0) * (-p).ln_1p()).sqrt()
+    }
+}
+
+/// Distribution described by a set of samples, e.g. a Monte Carlo result.
+#[derive(Clone)]
+pub struct Empirical<T = f64> {
+    samples: Vec<T>,
+}
+
+impl<T: Float> Empirical<T> {
+    pub fn new(mut samples: Vec<T>) -> Result<Empirical<T>> {
+        if samples.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+        if let Some(x) = samples.iter().find(|x| !x.is_finite()) {
+            return Err(Error::NonFiniteSample(to_f64(*x)));
+        }
+        samples.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Ok(Empirical { samples })
+    }
+
+    /// The samples, sorted ascending.
+    pub fn samples(&self) -> &[T] {
+        &self.samples
+    }
+
+    pub fn mean(&self) -> T {
+        let n = T::from(self.samples.len()).unwrap();
+        self.samples.iter().fold(T::zero(), |acc, &x| acc + x) / n
+    }
+
+    pub fn variance(&self) -> T {
+        let n = T::from(self.samples.len()).unwrap();
+        let mean = self.mean();
+        self.samples
+            .iter()
+            .fold(T::zero(), |acc, &x| acc + (x - mean) * (x - mean))
+            / n
+    }
+
+    pub fn central_moment(&self, k: i32) -> T {
+        let n = T::from(self.samples.len()).unwrap();
+        let mean = self.mean();
+        self.samples
+            .iter()
+            .fold(T::zero(), |acc, &x| acc + (x - mean).powi(k))
+            / n
+    }
+
+    pub fn cdf(&self, x: T) -> T {
+        let below = self.samples.partition_point(|&s| s <= x);
+        T::from(below).unwrap() / T::from(self.samples.len()).unwrap()
+    }
+
+    /// Survival function, `1 - cdf(x)`.
+    pub fn sf(&self, x: T) -> T {
+        let below = self.samples.partition_point(|&s| s <= x);
+        T::from(self.samples.len() - below).unwrap() / T::from(self.samples.len()).unwrap()
+    }
+
+    pub fn quantile(&self, p: T) -> T {
+        let n = self.samples.len();
+        let i = (p * T::from(n).unwrap()).floor().to_usize().unwrap_or(0);
+        self.samples[i.min(n - 1)]
+    }
+}
+
+/// Mean, variance and shape of a distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Moments<T = f64> {
+    pub mean: T,
+    pub variance: T,
+    pub skewness: T,
+    /// Kurtosis, 3 for a normal distribution.
+    pub kurtosis: T,
+}
+
+/// Distribution described by its density at points of a grid, e.g. a fitted
+/// Pearson curve. The density is linear between points and zero outside.
+#[derive(Clone)]
+pub struct Tabulated<T = f64> {
+    x: Vec<T>,
+    pdf: Vec<T>,
+    /// Probability below each point.
+    below: Vec<T>,
+    /// Probability above each point, kept separately so that upper tails
+    /// are not lost to cancellation.
+    above: Vec<T>,
+}
+
+impl<T: Float> Tabulated<T> {
+    /// Tabulate `density` at the strictly increasing points `x`. The density
+    /// need not be normalized.
+    ///
+    /// # Panics
+    ///
+    /// If `x` and `density` differ in length.
+    pub fn new(x: Vec<T>, density: Vec<T>) -> Result<Tabulated<T>> {
+        assert_eq!(x.len(), density.len());
+        if x.len() < 2 {
+            return Err(Error::EmptyInput);
+        }
+        if let Some(x) = x.iter().find(|x| !x.is_finite()) {
+            return Err(Error::NonFiniteSample(to_f64(*x)));
+        }
+        if let Some(w) = x.windows(2).find(|w| w[0] >= w[1]) {
+            return Err(Error::InvertedLimits {
+                lower: to_f64(w[0]),
+                upper: to_f64(w[1]),
+            });
+        }
+        if let Some(p) = density
+            .iter()
+            .find(|p| !(p.is_finite() && **p >= T::zero()))
+        {
+            return Err(Error::InvalidDensity(to_f64(*p)));
+        }
+        let masses: Vec<T> = (0..x.len() - 1)
+            .map(|i| (density[i] + density[i + 1]) / cast(2.0) * (x[i + 1] - x[i]))
+            .collect();
+        let area = masses.iter().fold(T::zero(), |acc, &m| acc + m);
+        if area <= T::zero() {
+            return Err(Error::InvalidDensity(to_f64(area)));
+        }
+        let pdf: Vec<T> = density.iter().map(|&p| p / area).collect();
+        let mut below = vec![T::zero(); x.len()];
+        let mut above = vec![T::zero(); x.len()];
+        for i in 0..masses.len() {
+            below[i + 1] = below[i] + masses[i] / area;
+        }
+        for i in (0..masses.len()).rev() {
+            above[i] = above[i + 1] + masses[i] / area;
+        }
+        Ok(Tabulated {
+            x,
+            pdf,
+            below,
+            above,
+        })
+    }
+
+    /// Fit a member of the Pearson family of distributions, which covers
+    /// every combination of skewness and kurtosis, by integrating
+    /// `p'/p = -(a + z) / (b0 + a z + b2 z^2)` over the standardized range
+    /// +/- 12 sigma or up to the bounds of the support.
+    pub fn pearson(moments: &Moments<T>) -> Result<Tabulated<T>> {
+        let std_dev = moments.variance.sqrt();
+        if !(std_dev > T::zero() && std_dev.is_finite()) {
+            return Err(Error::InvalidStdDev(to_f64(std_dev)));
+        }
+        let skewness = to_f64(moments.skewness);
+        let kurtosis = to_f64(moments.kurtosis);
+        let beta_1 = skewness * skewness;
+        if kurtosis.is_nan() || kurtosis <= beta_1 + 1.0 {
+            return Err(Error::InvalidMoments { skewness, kurtosis });
+        }
+        // coefficients scaled by 10 b2 - 12 b1 - 18, which vanishes for the
+        // uniform distribution
+        let d = 10.0 * kurtosis - 12.0 * beta_1 - 18.0;
+        let a = skewness * (kurtosis + 3.0);
+        let b0 = 4.0 * kurtosis - 3.0 * beta_1;
+        let b2 = 2.0 * kurtosis - 3.0 * beta_1 - 6.0;
+        let q = |z: f64| b0 + a * z + b2 * z * z;
+
+        // the support is the interval about the mean where q > 0
+        let (mut lower, mut upper) = (-12.0f64, 12.0f64);
+        let roots: Vec<f64> = if b2 == 0.0 {
+            if a == 0.0 { vec![] } else { vec![-b0 / a] }
+        } else {
+            let disc = a * a - 4.0 * b0 * b2;
+            if disc < 0.0 {
+                vec![]
+            } else {
+                vec![
+                    (-a - disc.sqrt()) / (2.0 * b2),
+                    (-a + disc.sqrt()) / (2.0 * b2),
+                ]
+            }
+        };
+        for root in roots {
+            if root < 0.0 {
+                lower = lower.max(root);
+            } else {
+                upper = upper.min(root);
+            }
+        }
+
+        const POINTS: usize = 4001;
+        let h = (upper - lower) / (POINTS - 1) as f64;
+        let z: Vec<f64> = (0..POINTS).map(|i| lower + h * i as f64).collect();
+        let mut log_p = vec![0.0; POINTS];
+        for i in 1..POINTS {
+            let mid = z[i] - h / 2.0;
+            log_p[i] = log_p[i - 1] - h * (a + d * mid) / q(mid);
+        }
+        let max = log_p.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mean = moments.mean;
+        Tabulated::new(
+            z.iter().map(|&z| mean + std_dev * cast(z)).collect(),
+            log_p.iter().map(|&l| cast((l - max).exp())).collect(),
+        )
+    }
+
+    /// Index of the cell containing `x`, if any.
+    fn cell(&self, x: T) -> Option<usize> {
+        if x < self.x[0] || x >= self.x[self.x.len() - 1] {
+            return None;
+        }
+        Some(self.x.partition_point(|&p| p <= x) - 1)
+    }
+
+    /// Probability between the start of cell `i` and `x`.
+    fn partial(&self, i: usize, x: T) -> T {
+        let slope = (self.pdf[i + 1] - self.pdf[i]) / (self.x[i + 1] - self.x[i]);
+        let s = x - self.x[i];
+        s * (self.pdf[i] + slope * s / cast(2.0))
+    }
+
+    pub fn pdf(&self, x: T) -> T {
+        match self.cell(x) {
+            Some(i) => {
+                let w = (x - self.x[i]) / (self.x[i + 1] - self.x[i]);
+                self.pdf[i] + w * (self.pdf[i + 1] - self.pdf[i])
+            }
+            None => T::zero(),
+        }
+    }
+
+    /// Integral of `g(x) * pdf(x)` over the support, by the trapezoid rule.
+    fn expect(&self, g: impl Fn(T) -> T) -> T {
+        (0..self.x.len() - 1).fold(T::zero(), |acc, i| {
+            let width = self.x[i + 1] - self.x[i];
+            acc + (g(self.x[i]) * self.pdf[i] + g(self.x[i + 1]) * self.pdf[i + 1]) / cast(2.0)
+                * width
+        })
+    }
+
+    pub fn mean(&self) -> T {
+        self.expect(|x| x)
+    }
+
+    pub fn variance(&self) -> T {
+        self.central_moment(2)
+    }
+
+    pub fn central_moment(&self, k: i32) -> T {
+        let mean = self.mean();
+        self.expect(|x| (x - mean).powi(k))
+    }
+
+    pub fn cdf(&self, x: T) -> T {
+        if x < self.x[0] {
+            return T::zero();
+        }
+        match self.cell(x) {
+            Some(i) => self.below[i] + self.partial(i, x),
+            None => T::one(),
+        }
+    }
+
+    /// Survival function, `1 - cdf(x)`.
+    pub fn sf(&self, x: T) -> T {
+        if x < self.x[0] {
+            return T::one();
+        }
+        match self.cell(x) {
+            Some(i) => {
+                let cell = self.below[i + 1] - self.below[i];
+                self.above[i + 1] + (cell - self.partial(i, x))
+            }
+            None => T::zero(),
+        }
+    }
+
+    pub fn quantile(&self, p: T) -> T {
+        let n = self.x.len();
+        let i = self.below.partition_point(|&b| b < p).clamp(1, n - 1) - 1;
+        // solve partial(i, x) = r for the linear density in the cell
+        let r = (p - self.below[i]).max(T::zero());
+        let slope = (self.pdf[i + 1] - self.pdf[i]) / (self.x[i + 1] - self.x[i]);
+        let root = (self.pdf[i] * self.pdf[i] + cast::<T>(2.0) * slope * r)
+            .max(T::zero())
+            .sqrt();
+        let denominator = self.pdf[i] + root;
+        let s = if denominator > T::zero() {
+            cast::<T>(2.0) * r / denominator
+        } else {
+            T::zero()
+        };
+        (self.x[i] + s).min(self.x[i + 1])
     }
 }
 
@@ -79,7 +650,7 @@ mod tests {
 
     #[test]
     fn test_dist_uniform() {
-        let dist = Uniform::new(1.0, 2.0);
+        let dist = Uniform::new(1.0, 2.0).unwrap();
         assert_eq!(dist.pdf(0.0), 0.0);
         // assert_eq!(dist.pdf(1.5), 0.5);
         assert_eq!(dist.pdf(2.5), 0.0);
@@ -87,4 +658,145 @@ mod tests {
         assert_eq!(dist.cdf(1.5), 0.5);
         assert_eq!(dist.cdf(2.5), 1.0);
     }
+
+    #[test]
+    fn test_dist_normal_tail() {
+        // a 6 sigma tail is ~1e-9, far below f32 resolution near 1.0
+        let dist = Normal::new(0.0, 1.0).unwrap();
+        assert!((dist.sf(6.0) - 9.865876e-10).abs() < 1e-15);
+        assert!((dist.cdf(-6.0) - dist.sf(6.0)).abs() < 1e-20);
+        assert_eq!(dist.cdf(0.0), 0.5);
+    }
+
+    #[test]
+    fn test_dist_invalid() {
+        assert_eq!(
+            Uniform::new(2.0, 1.0).err(),
+            Some(Error::InvertedLimits {
+                lower: 2.0,
+                upper: 1.0
+            })
+        );
+        assert_eq!(Normal::new(0.0, 0.0).err(), Some(Error::InvalidStdDev(0.0)));
+        assert_eq!(
+            Normal::new(0.0, -1.0).err(),
+            Some(Error::InvalidStdDev(-1.0))
+        );
+        assert_eq!(Normal::<f64>::fit(&[]).err(), Some(Error::EmptyInput));
+        assert!(Normal::fit(&[1.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn test_dist_quantile() {
+        let normal = Normal::new(1.0, 2.0).unwrap();
+        assert!((normal.quantile(0.5) - 1.0).abs() < 1e-12);
+        assert!((normal.cdf(normal.quantile(0.975)) - 0.975).abs() < 1e-9);
+        let uniform = Uniform::new(1.0, 2.0).unwrap();
+        assert_eq!(uniform.quantile(0.25), 1.25);
+    }
+
+    #[test]
+    fn test_dist_empirical() {
+        let dist = Empirical::new(vec![3.0, 1.0, 2.0, 4.0]).unwrap();
+        assert_eq!(dist.samples(), &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(dist.mean(), 2.5);
+        assert_eq!(dist.variance(), 1.25);
+        assert_eq!(dist.cdf(2.0), 0.5);
+        assert_eq!(dist.sf(2.0), 0.5);
+        assert_eq!(dist.cdf(0.0), 0.0);
+        assert_eq!(dist.quantile(0.6), 3.0);
+        assert_eq!(dist.quantile(1.0), 4.0);
+        assert_eq!(Empirical::<f64>::new(vec![]).err(), Some(Error::EmptyInput));
+    }
+
+    #[test]
+    fn test_dist_central_moments() {
+        let normal = Distribution::Normal(Normal::new(1.0, 2.0).unwrap());
+        assert_eq!(normal.central_moment(3), 0.0);
+        assert_eq!(normal.kurtosis(), 3.0);
+        let uniform = Distribution::Uniform(Uniform::new(-1.0, 1.0).unwrap());
+        assert!((uniform.variance() - 1.0 / 3.0).abs() < 1e-15);
+        assert!((uniform.kurtosis() - 1.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dist_scaled() {
+        let dist = Distribution::Empirical(Empirical::new(vec![1.0, 2.0, 4.0]).unwrap());
+        let mirrored = dist.scaled(-1.0);
+        assert_eq!(mirrored.quantile(0.0), -4.0);
+        assert_eq!(mirrored.cdf(-2.0), 2.0 / 3.0);
+        let fit = Tabulated::pearson(&Moments {
+            mean: 1.0,
+            variance: 1.0,
+            skewness: 2.0,
+            kurtosis: 9.0,
+        })
+        .unwrap();
+        let mirrored = Distribution::Tabulated(fit.clone()).scaled(-2.0);
+        assert!((mirrored.mean() + 2.0 * fit.mean()).abs() < 1e-9);
+        assert!((mirrored.sf(-2.5) - fit.cdf(1.25)).abs() < 1e-12);
+        assert!((mirrored.cdf(-2.5) - fit.sf(1.25)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_dist_rayleigh() {
+        let dist = Rayleigh::new(2.0).unwrap();
+        assert!((dist.cdf(dist.quantile(0.9)) - 0.9).abs() < 1e-12);
+        assert!((dist.mean() - 2.0 * (PI / 2.0).sqrt()).abs() < 1e-12);
+        assert_eq!(dist.sf(-1.0), 1.0);
+        assert_eq!(Rayleigh::new(0.0).err(), Some(Error::InvalidStdDev(0.0)));
+    }
+
+    #[test]
+    fn test_dist_pearson() {
+        // normal
+        let fit = Tabulated::pearson(&Moments {
+            mean: 1.0,
+            variance: 4.0,
+            skewness: 0.0,
+            kurtosis: 3.0,
+        })
+        .unwrap();
+        let normal = Normal::new(1.0, 2.0).unwrap();
+        assert!((fit.mean() - 1.0).abs() < 1e-9);
+        assert!((fit.variance() - 4.0).abs() < 1e-4);
+        assert!((fit.cdf(-3.0) - normal.cdf(-3.0)).abs() < 1e-6);
+        assert!((fit.sf(7.0) / normal.sf(7.0) - 1.0).abs() < 1e-2);
+        assert!((fit.quantile(fit.cdf(2.5)) - 2.5).abs() < 1e-9);
+
+        // uniform on +/- sqrt(3)
+        let fit = Tabulated::pearson(&Moments {
+            mean: 0.0,
+            variance: 1.0,
+            skewness: 0.0,
+            kurtosis: 1.8,
+        })
+        .unwrap();
+        assert!((fit.cdf(0.5) - (0.5 / 3f64.sqrt() + 1.0) / 2.0).abs() < 1e-9);
+
+        // exponential, skewness 2 and kurtosis 9, starting at mean - std_dev
+        let fit = Tabulated::pearson(&Moments {
+            mean: 1.0,
+            variance: 1.0,
+            skewness: 2.0,
+            kurtosis: 9.0,
+        })
+        .unwrap();
+        assert!((fit.cdf(1.0) - (1.0 - (-1f64).exp())).abs() < 1e-5);
+        assert!((Distribution::Tabulated(fit).skewness() - 2.0).abs() < 1e-2);
+
+        assert_eq!(
+            Tabulated::pearson(&Moments {
+                mean: 0.0,
+                variance: 1.0,
+                skewness: 1.0,
+                kurtosis: 1.5,
+            })
+            .err(),
+            Some(Error::InvalidMoments {
+                skewness: 1.0,
+                kurtosis: 1.5
+            })
+        );
+    }
 }
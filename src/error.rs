@@ -0,0 +1,220 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Errors raised when a dimension, distribution or analysis would otherwise
+/// produce NaN or infinite results.
+///
+/// Values are reported as `f64` regardless of the float type in use.
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// Standard deviation is zero, negative or not finite.
+    InvalidStdDev(f64),
+    /// Target process sigma is zero, negative or not finite.
+    InvalidProcessSigma(f64),
+    /// Lower limit is greater than the upper limit.
+    InvertedLimits { lower: f64, upper: f64 },
+    /// Not enough dimensions in the stack for the analysis.
+    TooFewDims {
+        analysis: &'static str,
+        required: usize,
+        found: usize,
+    },
+    /// No measurements were given.
+    EmptyInput,
+    /// A sample was NaN or infinite.
+    NonFiniteSample(f64),
+    /// The analysis needs process data the named dim does not have.
+    MissingProcessData(String),
+    /// A mean shift factor is outside [0, 1].
+    InvalidShift(f64),
+    /// A geometric tolerance zone, or a size it is found from, is out of
+    /// range or not finite.
+    InvalidZone(f64),
+    /// A density was negative, not finite, or integrated to zero.
+    InvalidDensity(f64),
+    /// No distribution has this skewness and (non-excess) kurtosis, which
+    /// requires `kurtosis > skewness^2 + 1`.
+    InvalidMoments { skewness: f64, kurtosis: f64 },
+    /// A correlation matrix entry is not symmetric, outside [-1, 1], or a
+    /// diagonal entry is not 1.
+    InvalidCorrelation { row: usize, col: usize, value: f64 },
+    /// A correlation matrix is not positive semi-definite.
+    NotPositiveDefinite,
+    /// A measurement direction is zero or not finite.
+    InvalidDirection([f64; 3]),
+    /// The hole is never larger than the pin, by their limits or their
+    /// distributions.
+    NoClearance,
+    /// No dim in the stack varies, so there is no distribution to find.
+    NoVariation,
+    /// The analysis needs independent dims, but the stack correlates some.
+    CorrelatedDims(&'static str),
+    /// No dim of that name in the stack or project.
+    UnknownDim(String),
+    /// A dim of that name is already in the project.
+    DuplicateDim(String),
+    /// No stack of that name in the project.
+    UnknownStack(String),
+    /// No material of that name in the library.
+    UnknownMaterial(String),
+    /// A line of a material file is not `name, cte, modulus`.
+    InvalidMaterialLine { line: usize, text: String },
+    /// The file at `path` could not be read, with the error shared so that
+    /// `Error` stays `Clone`.
+    Io {
+        path: PathBuf,
+        source: Arc<io::Error>,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidStdDev(std_dev) => {
+                write!(f, "standard deviation must be positive, got {std_dev}")
+            }
+            Error::InvalidProcessSigma(sigma) => {
+                write!(f, "target process sigma must be positive, got {sigma}")
+            }
+            Error::InvertedLimits { lower, upper } => write!(
+                f,
+                "lower limit {lower} must be less than upper limit {upper}"
+            ),
+            Error::TooFewDims {
+                analysis,
+                required,
+                found,
+            } => write!(
+                f,
+                "{analysis} analysis needs at least {required} dims, stack has {found}"
+            ),
+            Error::EmptyInput => write!(f, "no measurements given"),
+            Error::NonFiniteSample(x) => write!(f, "sample {x} is not finite"),
+            Error::MissingProcessData(name) => write!(f, "'{name}' has no process data"),
+            Error::InvalidShift(shift) => {
+                write!(f, "mean shift factor must be between 0 and 1, got {shift}")
+            }
+            Error::InvalidZone(zone) => {
+                write!(f, "tolerance zone or size {zone} is not valid")
+            }
+            Error::InvalidDensity(p) => write!(f, "density {p} is not a valid density"),
+            Error::InvalidMoments { skewness, kurtosis } => write!(
+                f,
+                "no distribution has skewness {skewness} and kurtosis {kurtosis}"
+            ),
+            Error::InvalidCorrelation { row, col, value } => {
+                write!(f, "correlation {value} at ({row}, {col}) is not valid")
+            }
+            Error::NotPositiveDefinite => {
+                write!(f, "correlation matrix is not positive semi-definite")
+            }
+            Error::InvalidDirection(direction) => {
+                write!(f, "direction {direction:?} must be finite and nonzero")
+            }
+            Error::NoClearance => write!(f, "the hole is never larger than the pin"),
+            Error::NoVariation => write!(f, "no dim in the stack varies"),
+            Error::CorrelatedDims(analysis) => {
+                write!(f, "{analysis} analysis needs independent dims")
+            }
+            Error::UnknownDim(name) => write!(f, "no dim named '{name}'"),
+            Error::DuplicateDim(name) => write!(f, "a dim named '{name}' already exists"),
+            Error::UnknownStack(name) => write!(f, "no stack named '{name}'"),
+            Error::UnknownMaterial(name) => write!(f, "no material named '{name}'"),
+            Error::InvalidMaterialLine { line, text } => write!(
+                f,
+                "line {line} should be 'name, cte, modulus', got '{text}'"
+            ),
+            Error::Io { path, source } => write!(f, "{}: {source}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// `io::Error` has no equality, so `Io` errors compare by path and kind.
+impl PartialEq for Error {
+    fn eq(&self, other: &Error) -> bool {
+        match self {
+            Error::InvalidStdDev(a) => matches!(other, Error::InvalidStdDev(b) if a == b),
+            Error::InvalidProcessSigma(a) => {
+                matches!(other, Error::InvalidProcessSigma(b) if a == b)
+            }
+            Error::InvertedLimits { lower, upper } => matches!(
+                other,
+                Error::InvertedLimits {
+                    lower: other_lower,
+                    upper: other_upper,
+                } if lower == other_lower && upper == other_upper
+            ),
+            Error::TooFewDims {
+                analysis,
+                required,
+                found,
+            } => matches!(
+                other,
+                Error::TooFewDims {
+                    analysis: other_analysis,
+                    required: other_required,
+                    found: other_found,
+                } if analysis == other_analysis && required == other_required && found == other_found
+            ),
+            Error::EmptyInput => matches!(other, Error::EmptyInput),
+            Error::NonFiniteSample(a) => matches!(other, Error::NonFiniteSample(b) if a == b),
+            Error::MissingProcessData(a) => {
+                matches!(other, Error::MissingProcessData(b) if a == b)
+            }
+            Error::InvalidShift(a) => matches!(other, Error::InvalidShift(b) if a == b),
+            Error::InvalidZone(a) => matches!(other, Error::InvalidZone(b) if a == b),
+            Error::InvalidDensity(a) => matches!(other, Error::InvalidDensity(b) if a == b),
+            Error::InvalidMoments { skewness, kurtosis } => matches!(
+                other,
+                Error::InvalidMoments {
+                    skewness: other_skewness,
+                    kurtosis: other_kurtosis,
+                } if skewness == other_skewness && kurtosis == other_kurtosis
+            ),
+            Error::InvalidCorrelation { row, col, value } => matches!(
+                other,
+                Error::InvalidCorrelation {
+                    row: other_row,
+                    col: other_col,
+                    value: other_value,
+                } if row == other_row && col == other_col && value == other_value
+            ),
+            Error::NotPositiveDefinite => matches!(other, Error::NotPositiveDefinite),
+            Error::InvalidDirection(a) => matches!(other, Error::InvalidDirection(b) if a == b),
+            Error::NoClearance => matches!(other, Error::NoClearance),
+            Error::NoVariation => matches!(other, Error::NoVariation),
+            Error::CorrelatedDims(a) => matches!(other, Error::CorrelatedDims(b) if a == b),
+            Error::UnknownDim(a) => matches!(other, Error::UnknownDim(b) if a == b),
+            Error::DuplicateDim(a) => matches!(other, Error::DuplicateDim(b) if a == b),
+            Error::UnknownStack(a) => matches!(other, Error::UnknownStack(b) if a == b),
+            Error::UnknownMaterial(a) => matches!(other, Error::UnknownMaterial(b) if a == b),
+            Error::InvalidMaterialLine { line, text } => matches!(
+                other,
+                Error::InvalidMaterialLine {
+                    line: other_line,
+                    text: other_text,
+                } if line == other_line && text == other_text
+            ),
+            Error::Io { path, source } => matches!(
+                other,
+                Error::Io {
+                    path: other_path,
+                    source: other_source,
+                } if path == other_path && source.kind() == other_source.kind()
+            ),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,153 @@
+use crate::dim::{Basic, Vec1D};
+use crate::error::{Error, Result};
+use crate::tol::Bilateral;
+use crate::utils::to_f64;
+
+use num_traits::Float;
+
+/// How the position tolerance of a fixed fastener's threaded or press fit
+/// hole is applied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Threaded<T = f64> {
+    /// The tolerance applies in a projected zone as tall as the mating part.
+    Projected,
+    /// The tolerance applies in the hole only, so its out of squareness is
+    /// magnified over the `projection` of the fastener above the hole, for
+    /// an `engagement` depth of thread.
+    Unprojected { projection: T, engagement: T },
+}
+
+/// Fasteners passing through clearance holes in both parts, e.g. a bolt and
+/// nut. From ASME Y14.5, `H = F + T` with the same `T` on both parts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Floating<T = f64> {
+    /// Diameter of the fastener at MMC.
+    pub fastener: T,
+}
+
+impl<T: Float> Floating<T> {
+    /// Fails unless the fastener size is positive.
+    pub fn new(fastener: T) -> Result<Floating<T>> {
+        Ok(Floating {
+            fastener: positive(fastener)?,
+        })
+    }
+
+    /// Smallest hole that assembles with a position tolerance `position` at
+    /// MMC on every hole.
+    pub fn hole_mmc(&self, position: T) -> T {
+        self.fastener + position
+    }
+
+    /// Position tolerance at MMC allowed on every hole of size `hole_mmc`.
+    pub fn position(&self, hole_mmc: T) -> Result<T> {
+        allowed(hole_mmc - self.fastener)
+    }
+
+    /// Hole dimension at the size needed for `position`, with the size
+    /// tolerance all above MMC. Fails if either is negative.
+    pub fn hole(&self, name: &str, position: T, size_tol: T) -> Result<Basic<T>> {
+        non_negative(position)?;
+        hole(name, self.hole_mmc(position), size_tol)
+    }
+}
+
+/// Fasteners held in one of the parts, e.g. a screw in a tapped hole or a
+/// press fit pin. From ASME Y14.5, `H = F + T1 + T2` for a clearance hole
+/// tolerance `T1` and threaded hole tolerance `T2`, or
+/// `H = F + T1 + T2 (1 + 2P / D)` when `T2` is not in a projected zone.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fixed<T = f64> {
+    /// Diameter of the fastener at MMC.
+    pub fastener: T,
+    pub threaded: Threaded<T>,
+}
+
+impl<T: Float> Fixed<T> {
+    /// Fails unless the fastener size and any thread engagement are
+    /// positive and any projection is not negative.
+    pub fn new(fastener: T, threaded: Threaded<T>) -> Result<Fixed<T>> {
+        if let Threaded::Unprojected {
+            projection,
+            engagement,
+        } = threaded
+        {
+            non_negative(projection)?;
+            positive(engagement)?;
+        }
+        Ok(Fixed {
+            fastener: positive(fastener)?,
+            threaded,
+        })
+    }
+
+    /// Factor on the threaded hole tolerance for out of squareness.
+    fn magnification(&self) -> T {
+        match self.threaded {
+            Threaded::Projected => T::one(),
+            Threaded::Unprojected {
+                projection,
+                engagement,
+            } => T::one() + (projection + projection) / engagement,
+        }
+    }
+
+    /// Smallest clearance hole that assembles with position tolerances at
+    /// MMC of `clearance` on the clearance hole and `threaded` on the
+    /// threaded hole.
+    pub fn hole_mmc(&self, clearance: T, threaded: T) -> T {
+        self.fastener + clearance + threaded * self.magnification()
+    }
+
+    /// Position tolerance at MMC allowed on both holes, split equally, for
+    /// a clearance hole of size `hole_mmc`.
+    pub fn position(&self, hole_mmc: T) -> Result<T> {
+        allowed((hole_mmc - self.fastener) / (T::one() + self.magnification()))
+    }
+
+    /// Clearance hole dimension at the size needed for the position
+    /// tolerances, with the size tolerance all above MMC. Fails if any of
+    /// them is negative.
+    pub fn hole(&self, name: &str, clearance: T, threaded: T, size_tol: T) -> Result<Basic<T>> {
+        non_negative(clearance)?;
+        non_negative(threaded)?;
+        hole(name, self.hole_mmc(clearance, threaded), size_tol)
+    }
+}
+
+fn allowed<T: Float>(position: T) -> Result<T> {
+    if position.is_nan() || position <= T::zero() {
+        return Err(Error::InvalidZone(to_f64(position)));
+    }
+    Ok(position)
+}
+
+fn positive<T: Float>(size: T) -> Result<T> {
+    if !(size > T::zero() && size.is_finite()) {
+        return Err(Error::InvalidZone(to_f64(size)));
+    }
+    Ok(size)
+}
+
+fn non_negative<T: Float>(size: T) -> Result<T> {
+    if !(size >= T::zero() && size.is_finite()) {
+        return Err(Error::InvalidZone(to_f64(size)));
+    }
+    Ok(size)
+}
+
+fn hole<T: Float>(name: &str, mmc: T, size_tol: T) -> Result<Basic<T>> {
+    if !(size_tol >= T::zero() && size_tol.is_finite()) {
+        return Err(Error::InvertedLimits {
+            lower: to_f64(mmc),
+            upper: to_f64(mmc + size_tol),
+        });
+    }
+    Ok(Basic::new(
+        Vec1D::new(mmc),
+        Bilateral::unequal(size_tol, T::zero()),
+        T::one(),
+        name.to_owned(),
+        "(clearance hole at MMC)".to_owned(),
+    ))
+}
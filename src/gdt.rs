@@ -0,0 +1,303 @@
+use crate::calc;
+use crate::dim::{Basic, Contributor, Reviewed, Vec1D};
+use crate::dist;
+use crate::error::{Error, Result};
+use crate::tol::Bilateral;
+use crate::utils::{cast, to_f64};
+
+use num_traits::Float;
+use std::fmt;
+
+/// Geometric characteristics that can contribute to a stack.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Characteristic {
+    /// Diametral zone around the true position of an axis.
+    Position,
+    /// Profile of a surface, bilateral about the true profile.
+    Profile,
+    Flatness,
+    Parallelism,
+    Perpendicularity,
+    /// Full indicator movement, twice the eccentricity of the feature.
+    Runout,
+    /// Diametral zone around the datum axis.
+    Concentricity,
+}
+
+impl Characteristic {
+    /// Whether the tolerance bounds a radial error, as opposed to a zone
+    /// between two parallel planes.
+    pub fn is_radial(&self) -> bool {
+        matches!(
+            self,
+            Characteristic::Position | Characteristic::Runout | Characteristic::Concentricity
+        )
+    }
+}
+
+impl fmt::Display for Characteristic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Characteristic::Position => "Position",
+            Characteristic::Profile => "Profile",
+            Characteristic::Flatness => "Flatness",
+            Characteristic::Parallelism => "Parallelism",
+            Characteristic::Perpendicularity => "Perpendicularity",
+            Characteristic::Runout => "Runout",
+            Characteristic::Concentricity => "Concentricity",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A geometric tolerance entered in a stack as its equivalent 1D bilateral
+/// contribution, centered on zero: a zone of 0.2 contributes +/- 0.1.
+///
+/// Planar zones are taken as normal with the zone at +/- 3 sigma. Radial
+/// errors are taken as Rayleigh, with the same probability of falling
+/// inside the zone. Seen along a stack in a random direction, a Rayleigh
+/// radial error is normal with the same scale, so that is the distribution
+/// the contribution carries.
+#[derive(Clone)]
+pub struct Geometric<T = f64> {
+    pub name: String,
+    pub desc: String,
+    pub characteristic: Characteristic,
+    /// Width of the tolerance zone, its diameter for a diametral zone.
+    pub zone: T,
+    pub a: T,
+}
+
+impl<T: Float> Geometric<T> {
+    pub fn new(name: &str, characteristic: Characteristic, zone: T) -> Result<Geometric<T>> {
+        check_zone(zone)?;
+        Ok(Geometric {
+            name: name.to_owned(),
+            desc: format!("{characteristic} {}", to_f64(zone)),
+            characteristic,
+            zone,
+            a: T::one(),
+        })
+    }
+
+    pub fn sensitivity(mut self, a: T) -> Geometric<T> {
+        self.a = a;
+        self
+    }
+
+    pub fn desc(mut self, desc: &str) -> Geometric<T> {
+        self.desc = desc.to_owned();
+        self
+    }
+
+    /// The equivalent bilateral dimension, +/- half the zone.
+    pub fn basic(&self) -> Basic<T> {
+        Basic::new(
+            Vec1D::new(T::zero()),
+            Bilateral::symmetric(self.zone / cast(2.0)),
+            self.a,
+            self.name.clone(),
+            self.desc.clone(),
+        )
+    }
+
+    /// Number of standard deviations between the center and the edge of
+    /// the equivalent bilateral tolerance.
+    pub fn process_sigma(&self) -> T {
+        if self.characteristic.is_radial() {
+            // the Rayleigh quantile with the coverage of +/- 3 sigma
+            let outside = cast::<T>(2.0)
+                * dist::Normal::new(T::zero(), T::one())
+                    .unwrap()
+                    .sf(cast(3.0));
+            (cast::<T>(-2.0) * outside.ln()).sqrt()
+        } else {
+            cast(3.0)
+        }
+    }
+
+    /// Distribution of the radial error, for radial characteristics.
+    pub fn radial_error(&self) -> Option<dist::Rayleigh<T>> {
+        if !self.characteristic.is_radial() {
+            return None;
+        }
+        dist::Rayleigh::new(self.zone / cast(2.0) / self.process_sigma()).ok()
+    }
+
+    /// The equivalent bilateral dimension with its distribution. Fails if
+    /// the zone has been set out of range since `new`.
+    pub fn reviewed(&self) -> Result<Reviewed<T>> {
+        check_zone(self.zone)?;
+        let sigma = self.process_sigma();
+        let std_dev = self.zone / cast(2.0) / sigma;
+        Ok(Reviewed {
+            dim: self.basic(),
+            target_process_sigma: sigma,
+            distribution: Some(dist::Distribution::Normal(dist::Normal::new(
+                T::zero(),
+                std_dev,
+            )?)),
+        })
+    }
+}
+
+fn check_zone<T: Float>(zone: T) -> Result<()> {
+    if !(zone > T::zero() && zone.is_finite()) {
+        return Err(Error::InvalidZone(to_f64(zone)));
+    }
+    Ok(())
+}
+
+/// Material condition a geometric tolerance applies at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Modifier {
+    /// Regardless of feature size, no bonus tolerance.
+    Rfs,
+    /// Maximum material condition, the smallest hole or largest pin.
+    Mmc,
+    /// Least material condition, the largest hole or smallest pin.
+    Lmc,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeatureKind {
+    /// A hole or slot.
+    Internal,
+    /// A pin or tab.
+    External,
+}
+
+/// A feature of size whose location is controlled by a geometric tolerance
+/// with a material condition modifier, entered in a stack as the single
+/// contribution `size.a * size + position.a * location error`.
+///
+/// The location error may use the position zone plus a bonus equal to the
+/// departure of the actual size from the modifier's condition, so size and
+/// location are correlated. `reviewed` carries that correlation into the WC
+/// limits and into the distribution sampled by Monte Carlo.
+#[derive(Clone)]
+pub struct FeatureOfSize<T = f64> {
+    pub name: String,
+    pub kind: FeatureKind,
+    /// The size, usually a diameter, and the sensitivity of the stack to it.
+    pub size: Contributor<T>,
+    pub position: Geometric<T>,
+    pub modifier: Modifier,
+}
+
+impl<T: Float> FeatureOfSize<T> {
+    pub fn new(
+        name: &str,
+        kind: FeatureKind,
+        size: impl Into<Contributor<T>>,
+        position: Geometric<T>,
+        modifier: Modifier,
+    ) -> FeatureOfSize<T> {
+        FeatureOfSize {
+            name: name.to_owned(),
+            kind,
+            size: size.into(),
+            position,
+            modifier,
+        }
+    }
+
+    /// Size at maximum material condition.
+    pub fn mmc(&self) -> T {
+        match self.kind {
+            FeatureKind::Internal => self.size.basic().absolute_lower(),
+            FeatureKind::External => self.size.basic().absolute_upper(),
+        }
+    }
+
+    /// Size at least material condition.
+    pub fn lmc(&self) -> T {
+        match self.kind {
+            FeatureKind::Internal => self.size.basic().absolute_upper(),
+            FeatureKind::External => self.size.basic().absolute_lower(),
+        }
+    }
+
+    /// Bonus tolerance at an actual size, never negative.
+    pub fn bonus(&self, size: T) -> T {
+        let departure = match (self.modifier, self.kind) {
+            (Modifier::Rfs, _) => T::zero(),
+            (Modifier::Mmc, FeatureKind::Internal) => size - self.mmc(),
+            (Modifier::Mmc, FeatureKind::External) => self.mmc() - size,
+            (Modifier::Lmc, FeatureKind::Internal) => self.lmc() - size,
+            (Modifier::Lmc, FeatureKind::External) => size - self.lmc(),
+        };
+        departure.max(T::zero())
+    }
+
+    /// Position zone allowed at an actual size.
+    pub fn zone(&self, size: T) -> T {
+        self.position.zone + self.bonus(size)
+    }
+
+    /// Contribution to the stack at an actual size, from the location error
+    /// at one side of the allowed zone to the other.
+    fn extremes(&self, size: T) -> (T, T) {
+        let center = self.size.basic().a * size;
+        let half = (self.position.a * self.zone(size) / cast(2.0)).abs();
+        (center - half, center + half)
+    }
+
+    /// Worst case limits of the contribution. Both the center and the zone
+    /// are linear in size, so the extremes are at the size limits.
+    pub fn basic(&self) -> Basic<T> {
+        let size = self.size.basic();
+        let (l_lower, l_upper) = self.extremes(size.absolute_lower());
+        let (u_lower, u_upper) = self.extremes(size.absolute_upper());
+        let mut basic = Basic::from_limits(
+            &self.name,
+            size.a * size.nom.x,
+            l_lower.min(u_lower),
+            l_upper.max(u_upper),
+        );
+        basic.desc = format!("({} at {:?})", self.position.desc, self.modifier);
+        basic
+    }
+
+    /// The contribution with its distribution: the mixture over the size
+    /// distribution of the normal location error allowed at each size, see
+    /// `Geometric`.
+    pub fn reviewed(&self) -> Result<Reviewed<T>> {
+        const SIZES: usize = 201;
+        const POINTS: usize = 2001;
+        let sizes: Vec<T> = match self.size.distribution() {
+            Some(d) => (0..SIZES)
+                .map(|k| d.quantile(cast((k as f64 + 0.5) / SIZES as f64)))
+                .collect(),
+            None => vec![self.size.basic().absolute_median()],
+        };
+        let sigma = self.position.process_sigma();
+        let components = sizes
+            .iter()
+            .map(|&size| {
+                let std_dev = (self.position.a * self.zone(size) / cast(2.0)).abs() / sigma;
+                dist::Normal::new(self.size.basic().a * size, std_dev)
+            })
+            .collect::<Result<Vec<dist::Normal<T>>>>()?;
+        let spread: T = cast(8.0);
+        let lower = components
+            .iter()
+            .fold(T::infinity(), |acc, c| acc.min(c.mean - spread * c.std_dev));
+        let upper = components.iter().fold(T::neg_infinity(), |acc, c| {
+            acc.max(c.mean + spread * c.std_dev)
+        });
+        let step = (upper - lower) / cast((POINTS - 1) as f64);
+        let x: Vec<T> = (0..POINTS).map(|i| lower + step * cast(i as f64)).collect();
+        let density = x
+            .iter()
+            .map(|&x| calc::sum(components.iter().map(|c| c.pdf(x))))
+            .collect();
+        Ok(Reviewed {
+            dim: self.basic(),
+            target_process_sigma: sigma,
+            distribution: Some(dist::Distribution::Tabulated(dist::Tabulated::new(
+                x, density,
+            )?)),
+        })
+    }
+}
@@ -1,7 +1,24 @@
 #![allow(dead_code)]
 pub mod calc;
+pub mod clearance;
+pub mod correlation;
 pub mod dim;
 pub mod dist;
+pub mod error;
+pub mod fastener;
+pub mod gdt;
+pub mod loop2d;
+pub mod loop3d;
+pub mod material;
+pub mod nonlinear;
+pub mod optimize;
+pub mod part;
+pub mod project;
+pub mod scenario;
+pub mod sobol;
 pub mod stats;
+pub mod sweep;
+pub mod thermal;
 pub mod tol;
 mod utils;
+pub mod validate;
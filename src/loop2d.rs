@@ -0,0 +1,216 @@
+use crate::calc;
+use crate::correlation::Correlation;
+use crate::dim::{Basic, Contributor, Reviewed};
+use crate::error::Result;
+
+use num_traits::Float;
+
+/// A dimension in a 2D vector loop: a length at an angle.
+///
+/// Both are ordinary 1D contributors, so they can carry process data. The
+/// angle is in radians, measured counter-clockwise from the x axis.
+#[derive(Clone)]
+pub struct Vector2D<T = f64> {
+    pub length: Contributor<T>,
+    pub angle: Contributor<T>,
+}
+
+impl<T: Float> Vector2D<T> {
+    pub fn new(length: impl Into<Contributor<T>>, angle: impl Into<Contributor<T>>) -> Vector2D<T> {
+        Vector2D {
+            length: length.into(),
+            angle: angle.into(),
+        }
+    }
+
+    /// Component of the vector along `direction` (radians).
+    fn project(length: T, angle: T, direction: T) -> T {
+        length * (angle - direction).cos()
+    }
+}
+
+/// A chain of 2D vectors whose sum, projected onto a measurement direction,
+/// is the gap of interest.
+///
+/// The closure equation is nonlinear in the angles, so WC and RSS use
+/// sensitivities from linearizing about the median of every dim, while
+/// `monte_carlo` evaluates the exact loop.
+pub struct Loop2D<T = f64> {
+    pub name: String,
+    pub description: String,
+    pub vectors: Vec<Vector2D<T>>,
+    /// Direction the gap is measured in, in radians.
+    pub direction: T,
+    /// Correlations between dims by name, see `correlate`.
+    pub correlations: Vec<(String, String, T)>,
+}
+
+impl<T: Float> Loop2D<T> {
+    pub fn new(
+        name: String,
+        description: String,
+        vectors: Vec<Vector2D<T>>,
+        direction: T,
+    ) -> Loop2D<T> {
+        Loop2D {
+            name,
+            description,
+            vectors,
+            direction,
+            correlations: Vec::new(),
+        }
+    }
+
+    /// Start building a loop measured along the x axis.
+    pub fn builder(name: &str) -> Loop2DBuilder<T> {
+        Loop2DBuilder {
+            lp: Loop2D::new(name.to_owned(), "".to_owned(), Vec::new(), T::zero()),
+        }
+    }
+
+    pub fn append(&mut self, vector: Vector2D<T>) {
+        self.vectors.push(vector);
+    }
+
+    /// Correlate the dims named `a` and `b`, see `Stack::correlate`.
+    pub fn correlate(&mut self, a: &str, b: &str, rho: T) -> Result<()> {
+        let names: Vec<&str> = self
+            .vectors
+            .iter()
+            .flat_map(|v| [v.length.name(), v.angle.name()])
+            .collect();
+        Correlation::correlate(&mut self.correlations, &names, a, b, rho)
+    }
+
+    /// Correlation matrix ordered as in `dims`, see `Stack::correlation`.
+    pub fn correlation(&self) -> Result<Option<Correlation<T>>> {
+        let names: Vec<&str> = self.dims().iter().map(|dim| dim.name()).collect();
+        Correlation::by_name(&names, &self.correlations)
+    }
+
+    /// The length and angle of every vector, in that order.
+    pub fn dims(&self) -> Vec<&Contributor<T>> {
+        self.vectors
+            .iter()
+            .flat_map(|v| [&v.length, &v.angle])
+            .collect()
+    }
+
+    /// Every dim with its limits as used in the analysis, the angles read as
+    /// signed values, see `calc::signed_angle`.
+    fn signed_dims(&self) -> Result<Vec<Contributor<T>>> {
+        let mut dims = Vec::with_capacity(2 * self.vectors.len());
+        for v in &self.vectors {
+            dims.push(v.length.clone());
+            dims.push(calc::signed_angle(&v.angle)?);
+        }
+        Ok(dims)
+    }
+
+    /// Gap for absolute values ordered as in `dims`.
+    pub fn gap(&self, x: &[T]) -> T {
+        calc::sum(
+            x.chunks(2)
+                .map(|v| Vector2D::project(v[0], v[1], self.direction)),
+        )
+    }
+
+    /// Gap with every dim at its median.
+    pub fn nominal(&self) -> T {
+        let x: Vec<T> = self
+            .vectors
+            .iter()
+            .flat_map(|v| {
+                [
+                    v.length.basic().absolute_median(),
+                    calc::signed_median(v.angle.basic()),
+                ]
+            })
+            .collect();
+        self.gap(&x)
+    }
+
+    /// Partial derivative of the gap with respect to each dim, ordered as in
+    /// `dims`. These are the `a` coefficients of the linearized loop.
+    pub fn sensitivities(&self) -> Vec<(String, T)> {
+        self.vectors
+            .iter()
+            .flat_map(|v| {
+                let length = v.length.basic().absolute_median();
+                let angle = calc::signed_median(v.angle.basic()) - self.direction;
+                [
+                    (v.length.name().to_owned(), angle.cos()),
+                    (v.angle.name().to_owned(), -length * angle.sin()),
+                ]
+            })
+            .collect()
+    }
+
+    fn half_tols(&self) -> Vec<T> {
+        let a: Vec<T> = self.sensitivities().into_iter().map(|(_, a)| a).collect();
+        calc::scaled_half_tols(&self.dims(), &a)
+    }
+
+    /// Worst case of the linearized loop.
+    pub fn wc(&self) -> Basic<T> {
+        let t_wc = calc::sum(self.half_tols().into_iter().map(|t| t.abs()));
+        calc::linearized(&self.name, "2D WC", self.nominal(), t_wc)
+    }
+
+    /// RSS of the linearized loop, see `calc::rss`.
+    pub fn rss(&self) -> Result<Basic<T>> {
+        let terms: Vec<T> = self
+            .dims()
+            .iter()
+            .zip(self.half_tols())
+            .map(|(dim, t)| dim.basic().nom.x.signum() * t)
+            .collect();
+        let t_rss = calc::correlated_rss(self.correlation()?.as_ref(), &terms);
+        Ok(calc::linearized(
+            &self.name,
+            "2D RSS",
+            self.nominal(),
+            t_rss,
+        ))
+    }
+
+    /// Monte Carlo simulation of the exact loop, see `calc::monte_carlo`.
+    pub fn monte_carlo(&self, at: T, samples: usize, seed: u64) -> Result<Reviewed<T>> {
+        let dims = self.signed_dims()?;
+        calc::simulate(
+            &self.name,
+            &dims.iter().collect::<Vec<_>>(),
+            |x| self.gap(x),
+            self.correlation()?.as_ref(),
+            at,
+            samples,
+            seed,
+        )
+    }
+}
+
+pub struct Loop2DBuilder<T = f64> {
+    lp: Loop2D<T>,
+}
+
+impl<T: Float> Loop2DBuilder<T> {
+    pub fn desc(mut self, description: &str) -> Loop2DBuilder<T> {
+        self.lp.description = description.to_owned();
+        self
+    }
+
+    /// Direction the gap is measured in, in radians.
+    pub fn direction(mut self, direction: T) -> Loop2DBuilder<T> {
+        self.lp.direction = direction;
+        self
+    }
+
+    pub fn push(mut self, vector: Vector2D<T>) -> Loop2DBuilder<T> {
+        self.lp.append(vector);
+        self
+    }
+
+    pub fn build(self) -> Loop2D<T> {
+        self.lp
+    }
+}
@@ -0,0 +1,365 @@
+use crate::calc;
+use crate::correlation::Correlation;
+use crate::dim::{Basic, Contributor, Reviewed};
+use crate::error::{Error, Result};
+use crate::stats;
+use crate::utils::to_f64;
+
+use num_traits::Float;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn index(&self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Motion {
+    Translation,
+    /// Rotation in radians, right handed about the axis.
+    Rotation,
+}
+
+type Matrix<T> = [[T; 4]; 4];
+
+/// One step of a 3D dimension chain: a translation along, or a rotation
+/// about, an axis of the current frame by the value of `dim`.
+#[derive(Clone)]
+pub struct Step3D<T = f64> {
+    pub motion: Motion,
+    pub axis: Axis,
+    pub dim: Contributor<T>,
+}
+
+impl<T: Float> Step3D<T> {
+    pub fn translate(axis: Axis, dim: impl Into<Contributor<T>>) -> Step3D<T> {
+        Step3D {
+            motion: Motion::Translation,
+            axis,
+            dim: dim.into(),
+        }
+    }
+
+    pub fn rotate(axis: Axis, dim: impl Into<Contributor<T>>) -> Step3D<T> {
+        Step3D {
+            motion: Motion::Rotation,
+            axis,
+            dim: dim.into(),
+        }
+    }
+
+    /// Homogeneous transform of the step for value `q`.
+    fn matrix(&self, q: T) -> Matrix<T> {
+        let mut m = identity();
+        let i = self.axis.index();
+        match self.motion {
+            Motion::Translation => m[i][3] = q,
+            Motion::Rotation => {
+                let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                m[j][j] = q.cos();
+                m[j][k] = -q.sin();
+                m[k][j] = q.sin();
+                m[k][k] = q.cos();
+            }
+        }
+        m
+    }
+
+    /// Derivative of `matrix` with respect to `q`.
+    fn derivative(&self, q: T) -> Matrix<T> {
+        let mut m = [[T::zero(); 4]; 4];
+        let i = self.axis.index();
+        match self.motion {
+            Motion::Translation => m[i][3] = T::one(),
+            Motion::Rotation => {
+                let (j, k) = ((i + 1) % 3, (i + 2) % 3);
+                m[j][j] = -q.sin();
+                m[j][k] = -q.cos();
+                m[k][j] = q.cos();
+                m[k][k] = -q.sin();
+            }
+        }
+        m
+    }
+}
+
+fn identity<T: Float>() -> Matrix<T> {
+    let mut m = [[T::zero(); 4]; 4];
+    for (i, row) in m.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+    m
+}
+
+fn mul<T: Float>(a: &Matrix<T>, b: &Matrix<T>) -> Matrix<T> {
+    let mut m = [[T::zero(); 4]; 4];
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = calc::sum((0..4).map(|k| a[i][k] * b[k][j]));
+        }
+    }
+    m
+}
+
+fn apply<T: Float>(m: &Matrix<T>, p: &[T; 4]) -> [T; 4] {
+    let mut out = [T::zero(); 4];
+    for (i, value) in out.iter_mut().enumerate() {
+        *value = calc::sum((0..4).map(|k| m[i][k] * p[k]));
+    }
+    out
+}
+
+/// A 3D dimension chain from a datum frame to a point, with the gap
+/// measured as the position of the point along a direction of the datum
+/// frame.
+///
+/// Each step is a homogeneous transform, so small rotations of one part
+/// carry through the lever arm of everything after it. WC and RSS use
+/// sensitivities from the Jacobian of the chain at the median of every dim,
+/// while `monte_carlo` evaluates the exact transforms.
+pub struct Loop3D<T = f64> {
+    pub name: String,
+    pub description: String,
+    pub steps: Vec<Step3D<T>>,
+    /// Point of interest, in the frame at the end of the chain.
+    pub point: [T; 3],
+    /// Direction the gap is measured in, in the datum frame.
+    pub direction: [T; 3],
+    /// Correlations between dims by name, see `correlate`.
+    pub correlations: Vec<(String, String, T)>,
+}
+
+impl<T: Float> Loop3D<T> {
+    /// Fails if `direction` is zero or not finite.
+    pub fn new(
+        name: String,
+        description: String,
+        steps: Vec<Step3D<T>>,
+        point: [T; 3],
+        direction: [T; 3],
+    ) -> Result<Loop3D<T>> {
+        check_direction(&direction)?;
+        Ok(Loop3D {
+            name,
+            description,
+            steps,
+            point,
+            direction,
+            correlations: Vec::new(),
+        })
+    }
+
+    /// Start building a chain to the end frame origin, measured along x.
+    pub fn builder(name: &str) -> Loop3DBuilder<T> {
+        Loop3DBuilder {
+            lp: Loop3D {
+                name: name.to_owned(),
+                description: "".to_owned(),
+                steps: Vec::new(),
+                point: [T::zero(); 3],
+                direction: [T::one(), T::zero(), T::zero()],
+                correlations: Vec::new(),
+            },
+        }
+    }
+
+    pub fn append(&mut self, step: Step3D<T>) {
+        self.steps.push(step);
+    }
+
+    /// Correlate the dims named `a` and `b`, see `Stack::correlate`.
+    pub fn correlate(&mut self, a: &str, b: &str, rho: T) -> Result<()> {
+        let names: Vec<&str> = self.steps.iter().map(|step| step.dim.name()).collect();
+        Correlation::correlate(&mut self.correlations, &names, a, b, rho)
+    }
+
+    /// Correlation matrix ordered as in `dims`, see `Stack::correlation`.
+    pub fn correlation(&self) -> Result<Option<Correlation<T>>> {
+        let names: Vec<&str> = self.dims().iter().map(|dim| dim.name()).collect();
+        Correlation::by_name(&names, &self.correlations)
+    }
+
+    /// The dim of every step, in order.
+    pub fn dims(&self) -> Vec<&Contributor<T>> {
+        self.steps.iter().map(|step| &step.dim).collect()
+    }
+
+    /// Every dim with its limits as used in the analysis, the rotations read
+    /// as signed angles, see `calc::signed_angle`.
+    fn signed_dims(&self) -> Result<Vec<Contributor<T>>> {
+        self.steps
+            .iter()
+            .map(|step| match step.motion {
+                Motion::Translation => Ok(step.dim.clone()),
+                Motion::Rotation => calc::signed_angle(&step.dim),
+            })
+            .collect()
+    }
+
+    fn unit_direction(&self) -> [T; 3] {
+        let norm = stats::rss(&self.direction);
+        self.direction.map(|x| x / norm)
+    }
+
+    fn project(&self, p: &[T; 4]) -> T {
+        let n = self.unit_direction();
+        n[0] * p[0] + n[1] * p[1] + n[2] * p[2]
+    }
+
+    fn homogeneous_point(&self) -> [T; 4] {
+        [self.point[0], self.point[1], self.point[2], T::one()]
+    }
+
+    /// Gap for absolute values ordered as in `dims`.
+    pub fn gap(&self, x: &[T]) -> T {
+        let h = self
+            .steps
+            .iter()
+            .zip(x)
+            .fold(identity(), |h, (step, &q)| mul(&h, &step.matrix(q)));
+        self.project(&apply(&h, &self.homogeneous_point()))
+    }
+
+    fn medians(&self) -> Vec<T> {
+        self.steps
+            .iter()
+            .map(|step| match step.motion {
+                Motion::Translation => step.dim.basic().absolute_median(),
+                Motion::Rotation => calc::signed_median(step.dim.basic()),
+            })
+            .collect()
+    }
+
+    /// Gap with every dim at its median.
+    pub fn nominal(&self) -> T {
+        self.gap(&self.medians())
+    }
+
+    /// Row of the Jacobian of the gap, i.e. the `a` coefficient of each dim
+    /// ordered as in `dims`.
+    pub fn sensitivities(&self) -> Vec<(String, T)> {
+        let q = self.medians();
+        let n = self.steps.len();
+        // transforms before each step, and the point as seen after each step
+        let mut prefix = vec![identity(); n];
+        for i in 1..n {
+            prefix[i] = mul(&prefix[i - 1], &self.steps[i - 1].matrix(q[i - 1]));
+        }
+        let mut suffix = vec![self.homogeneous_point(); n];
+        for i in (0..n.saturating_sub(1)).rev() {
+            suffix[i] = apply(&self.steps[i + 1].matrix(q[i + 1]), &suffix[i + 1]);
+        }
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let d = mul(&prefix[i], &step.derivative(q[i]));
+                (
+                    step.dim.name().to_owned(),
+                    self.project(&apply(&d, &suffix[i])),
+                )
+            })
+            .collect()
+    }
+
+    fn half_tols(&self) -> Vec<T> {
+        let a: Vec<T> = self.sensitivities().into_iter().map(|(_, a)| a).collect();
+        calc::scaled_half_tols(&self.dims(), &a)
+    }
+
+    /// Worst case of the linearized chain.
+    pub fn wc(&self) -> Basic<T> {
+        let t_wc = calc::sum(self.half_tols().into_iter().map(|t| t.abs()));
+        calc::linearized(&self.name, "3D WC", self.nominal(), t_wc)
+    }
+
+    /// RSS of the linearized chain, see `calc::rss`.
+    pub fn rss(&self) -> Result<Basic<T>> {
+        let terms: Vec<T> = self
+            .dims()
+            .iter()
+            .zip(self.half_tols())
+            .map(|(dim, t)| dim.basic().nom.x.signum() * t)
+            .collect();
+        let t_rss = calc::correlated_rss(self.correlation()?.as_ref(), &terms);
+        Ok(calc::linearized(
+            &self.name,
+            "3D RSS",
+            self.nominal(),
+            t_rss,
+        ))
+    }
+
+    /// Monte Carlo simulation of the exact chain, see `calc::monte_carlo`.
+    pub fn monte_carlo(&self, at: T, samples: usize, seed: u64) -> Result<Reviewed<T>> {
+        let dims = self.signed_dims()?;
+        calc::simulate(
+            &self.name,
+            &dims.iter().collect::<Vec<_>>(),
+            |x| self.gap(x),
+            self.correlation()?.as_ref(),
+            at,
+            samples,
+            seed,
+        )
+    }
+}
+
+pub struct Loop3DBuilder<T = f64> {
+    lp: Loop3D<T>,
+}
+
+impl<T: Float> Loop3DBuilder<T> {
+    pub fn desc(mut self, description: &str) -> Loop3DBuilder<T> {
+        self.lp.description = description.to_owned();
+        self
+    }
+
+    /// Point of interest, in the frame at the end of the chain.
+    pub fn point(mut self, point: [T; 3]) -> Loop3DBuilder<T> {
+        self.lp.point = point;
+        self
+    }
+
+    /// Direction the gap is measured in, in the datum frame.
+    pub fn direction(mut self, direction: [T; 3]) -> Loop3DBuilder<T> {
+        self.lp.direction = direction;
+        self
+    }
+
+    pub fn translate(mut self, axis: Axis, dim: impl Into<Contributor<T>>) -> Loop3DBuilder<T> {
+        self.lp.append(Step3D::translate(axis, dim));
+        self
+    }
+
+    pub fn rotate(mut self, axis: Axis, dim: impl Into<Contributor<T>>) -> Loop3DBuilder<T> {
+        self.lp.append(Step3D::rotate(axis, dim));
+        self
+    }
+
+    /// Fails if the direction is zero or not finite.
+    pub fn build(self) -> Result<Loop3D<T>> {
+        check_direction(&self.lp.direction)?;
+        Ok(self.lp)
+    }
+}
+
+fn check_direction<T: Float>(direction: &[T; 3]) -> Result<()> {
+    let norm = stats::rss(direction);
+    if norm > T::zero() && norm.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidDirection(direction.map(to_f64)))
+    }
+}
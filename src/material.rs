@@ -0,0 +1,162 @@
+use crate::error::{Error, Result};
+use crate::utils::cast;
+
+use num_traits::Float;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Built-in materials as `(name, cte per degree C, modulus in MPa)`, at room
+/// temperature. Values are typical handbook figures; use a material file
+/// for supplier data.
+const BUILTIN: &[(&str, f64, f64)] = &[
+    // steels
+    ("1018", 12.0e-6, 205_000.0),
+    ("1045", 11.2e-6, 206_000.0),
+    ("4140", 12.3e-6, 205_000.0),
+    ("304", 17.3e-6, 193_000.0),
+    ("316", 16.0e-6, 193_000.0),
+    ("17-4PH", 10.8e-6, 197_000.0),
+    // aluminum alloys
+    ("2024-T3", 23.2e-6, 73_100.0),
+    ("5052-H32", 23.8e-6, 70_300.0),
+    ("6061-T6", 23.6e-6, 68_900.0),
+    ("7075-T6", 23.6e-6, 71_700.0),
+    // brass
+    ("C260", 20.0e-6, 110_000.0),
+    ("C360", 20.5e-6, 97_000.0),
+    // plastics
+    ("ABS", 90.0e-6, 2_300.0),
+    ("PC", 65.0e-6, 2_400.0),
+    ("POM", 110.0e-6, 2_900.0),
+    ("PA66", 80.0e-6, 2_900.0),
+    ("PEEK", 47.0e-6, 3_600.0),
+    ("PTFE", 120.0e-6, 500.0),
+];
+
+/// A material with the properties used by the analyses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material<T = f64> {
+    pub name: String,
+    /// Coefficient of linear thermal expansion, per degree.
+    pub cte: T,
+    /// Elastic modulus, in MPa for dims in mm.
+    pub modulus: T,
+}
+
+impl<T: Float> Material<T> {
+    pub fn new(name: &str, cte: T, modulus: T) -> Material<T> {
+        Material {
+            name: name.to_owned(),
+            cte,
+            modulus,
+        }
+    }
+
+    /// A material from the built-in library, e.g. `"6061-T6"`.
+    pub fn named(name: &str) -> Result<Material<T>> {
+        Library::builtin().material(name)
+    }
+
+    /// Factor a length grows by for a change in temperature `delta`.
+    pub fn growth(&self, delta: T) -> T {
+        T::one() + self.cte * delta
+    }
+}
+
+/// A table of materials looked up by name, ignoring case.
+///
+/// Starts from the built-in table or empty, and can be extended in code or
+/// from files with one `name, cte, modulus` per line. Blank lines and lines
+/// starting with `#` are skipped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Library<T = f64> {
+    materials: Vec<Material<T>>,
+}
+
+impl<T: Float> Library<T> {
+    pub fn new() -> Library<T> {
+        Library {
+            materials: Vec::new(),
+        }
+    }
+
+    /// Common steels, aluminum alloys, brasses and plastics.
+    pub fn builtin() -> Library<T> {
+        let mut library = Library::new();
+        for &(name, cte, modulus) in BUILTIN {
+            library.insert(Material::new(name, cast(cte), cast(modulus)));
+        }
+        library
+    }
+
+    /// Add a material, replacing any of the same name.
+    pub fn insert(&mut self, material: Material<T>) {
+        match self
+            .materials
+            .iter_mut()
+            .find(|m| m.name.eq_ignore_ascii_case(&material.name))
+        {
+            Some(existing) => *existing = material,
+            None => self.materials.push(material),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Material<T>> {
+        self.materials
+            .iter()
+            .find(|m| m.name.eq_ignore_ascii_case(name))
+    }
+
+    /// A copy of the named material, to give to a dim.
+    pub fn material(&self, name: &str) -> Result<Material<T>> {
+        self.get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownMaterial(name.to_owned()))
+    }
+
+    /// The materials, in the order they were added.
+    pub fn materials(&self) -> &[Material<T>] {
+        &self.materials
+    }
+
+    /// Add every material in `text`, see `Library`.
+    pub fn extend_from_str(&mut self, text: &str) -> Result<()> {
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || Error::InvalidMaterialLine {
+                line: i + 1,
+                text: line.to_owned(),
+            };
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            let [name, cte, modulus] = fields[..] else {
+                return Err(invalid());
+            };
+            let cte: f64 = cte.parse().map_err(|_| invalid())?;
+            let modulus: f64 = modulus.parse().map_err(|_| invalid())?;
+            if name.is_empty() {
+                return Err(invalid());
+            }
+            self.insert(Material::new(name, cast(cte), cast(modulus)));
+        }
+        Ok(())
+    }
+
+    /// Add every material in the file at `path`, see `Library`.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source: Arc::new(source),
+        })?;
+        self.extend_from_str(&text)
+    }
+}
+
+impl<T: Float> Default for Library<T> {
+    fn default() -> Library<T> {
+        Library::new()
+    }
+}
@@ -0,0 +1,406 @@
+use crate::calc;
+use crate::correlation::Correlation;
+use crate::dim::{Basic, Contributor, Reviewed};
+use crate::dist;
+use crate::error::Result;
+use crate::sobol;
+use crate::utils::cast;
+
+use num_traits::Float;
+
+/// Above this many dims, `NonlinearStack::wc` stops enumerating every vertex
+/// and only evaluates the two vertices the sensitivities point to.
+const MAX_VERTEX_DIMS: usize = 16;
+
+/// Gap as a function of the absolute values of the dims.
+pub type GapFn<T> = Box<dyn Fn(&[T]) -> T>;
+
+/// A stack whose gap is an arbitrary function of the absolute values of its
+/// dims, ordered as in `dims`.
+///
+/// Sensitivities are found by central differences about the median of every
+/// dim, so `f` should be smooth over the tolerance zone.
+pub struct NonlinearStack<T = f64> {
+    pub name: String,
+    pub description: String,
+    pub dims: Vec<Contributor<T>>,
+    /// Correlations between dims by name, see `correlate`. The second order
+    /// analyses need independent dims.
+    pub correlations: Vec<(String, String, T)>,
+    f: GapFn<T>,
+}
+
+impl<T: Float> NonlinearStack<T> {
+    pub fn new(
+        name: String,
+        description: String,
+        dims: Vec<Contributor<T>>,
+        f: impl Fn(&[T]) -> T + 'static,
+    ) -> NonlinearStack<T> {
+        NonlinearStack {
+            name,
+            description,
+            dims,
+            correlations: Vec::new(),
+            f: Box::new(f),
+        }
+    }
+
+    pub fn builder(name: &str, f: impl Fn(&[T]) -> T + 'static) -> NonlinearStackBuilder<T> {
+        NonlinearStackBuilder {
+            stack: NonlinearStack::new(name.to_owned(), "".to_owned(), Vec::new(), f),
+        }
+    }
+
+    pub fn append(&mut self, dim: impl Into<Contributor<T>>) {
+        self.dims.push(dim.into());
+    }
+
+    /// Correlate the dims named `a` and `b`, see `Stack::correlate`.
+    pub fn correlate(&mut self, a: &str, b: &str, rho: T) -> Result<()> {
+        let names: Vec<&str> = self.dims.iter().map(|dim| dim.name()).collect();
+        Correlation::correlate(&mut self.correlations, &names, a, b, rho)
+    }
+
+    /// Correlation matrix ordered as in `dims`, see `Stack::correlation`.
+    pub fn correlation(&self) -> Result<Option<Correlation<T>>> {
+        let names: Vec<&str> = self.dims.iter().map(|dim| dim.name()).collect();
+        Correlation::by_name(&names, &self.correlations)
+    }
+
+    /// Gap for absolute values ordered as in `dims`.
+    pub fn gap(&self, x: &[T]) -> T {
+        (self.f)(x)
+    }
+
+    pub(crate) fn medians(&self) -> Vec<T> {
+        self.dims
+            .iter()
+            .map(|dim| dim.basic().absolute_median())
+            .collect()
+    }
+
+    fn steps(&self, x: &[T], power: T) -> Vec<T> {
+        self.dims
+            .iter()
+            .zip(x)
+            .map(|(dim, &x)| step(x, dim.basic().tol.t() / cast(2.0), power))
+            .collect()
+    }
+
+    /// Gap with every dim at its median.
+    pub fn nominal(&self) -> T {
+        self.gap(&self.medians())
+    }
+
+    /// Partial derivative of the gap with respect to each dim at the median.
+    /// These are the `a` coefficients of the linearized stack.
+    pub fn sensitivities(&self) -> Vec<(String, T)> {
+        self.dims
+            .iter()
+            .map(|dim| dim.name().to_owned())
+            .zip(self.gradient())
+            .collect()
+    }
+
+    pub(crate) fn gradient(&self) -> Vec<T> {
+        self.gradient_at(&self.medians())
+    }
+
+    fn gradient_at(&self, x: &[T]) -> Vec<T> {
+        let h = self.steps(x, cast(1.0 / 3.0));
+        (0..x.len())
+            .map(|i| {
+                let f_up = self.gap(&offset(x, &[(i, h[i])]));
+                let f_down = self.gap(&offset(x, &[(i, -h[i])]));
+                (f_up - f_down) / (cast::<T>(2.0) * h[i])
+            })
+            .collect()
+    }
+
+    /// Matrix of second partial derivatives of the gap at the median.
+    pub fn hessian(&self) -> Vec<Vec<T>> {
+        self.hessian_at(&self.medians())
+    }
+
+    fn hessian_at(&self, x: &[T]) -> Vec<Vec<T>> {
+        let h = self.steps(x, cast(0.25));
+        let f_0 = self.gap(x);
+        let n = x.len();
+        let mut hessian = vec![vec![T::zero(); n]; n];
+        for i in 0..n {
+            let f_up = self.gap(&offset(x, &[(i, h[i])]));
+            let f_down = self.gap(&offset(x, &[(i, -h[i])]));
+            hessian[i][i] = (f_up - cast::<T>(2.0) * f_0 + f_down) / (h[i] * h[i]);
+            for j in 0..i {
+                let f = |si: T, sj: T| self.gap(&offset(x, &[(i, si * h[i]), (j, sj * h[j])]));
+                let one = T::one();
+                let d = (f(one, one) - f(one, -one) - f(-one, one) + f(-one, -one))
+                    / (cast::<T>(4.0) * h[i] * h[j]);
+                hessian[i][j] = d;
+                hessian[j][i] = d;
+            }
+        }
+        hessian
+    }
+
+    /// Standard deviation of each dim, taking the tolerance as +/- 3 sigma as
+    /// `calc::rss` does.
+    pub(crate) fn std_devs(&self) -> Vec<T> {
+        self.dims
+            .iter()
+            .map(|dim| dim.basic().tol.t() / cast(6.0))
+            .collect()
+    }
+
+    /// Worst case found by evaluating `f` at every vertex of the tolerance
+    /// zone. Extremes inside the zone are not searched for.
+    ///
+    /// With more than 16 dims only the two vertices picked by the signs of
+    /// the sensitivities are evaluated.
+    pub fn wc(&self) -> Basic<T> {
+        let limits: Vec<(T, T)> = self
+            .dims
+            .iter()
+            .map(|dim| (dim.basic().absolute_lower(), dim.basic().absolute_upper()))
+            .collect();
+        let vertex = |upper: &dyn Fn(usize) -> bool| -> Vec<T> {
+            limits
+                .iter()
+                .enumerate()
+                .map(|(i, &(l, u))| if upper(i) { u } else { l })
+                .collect()
+        };
+        let nominal = self.nominal();
+        let (mut lower, mut upper) = (nominal, nominal);
+        let mut check = |x: Vec<T>| {
+            let value = self.gap(&x);
+            lower = lower.min(value);
+            upper = upper.max(value);
+        };
+        let n = limits.len();
+        let searched = if n <= MAX_VERTEX_DIMS {
+            for corner in 0..(1usize << n) {
+                check(vertex(&|i| corner & (1 << i) != 0));
+            }
+            1usize << n
+        } else {
+            let a = self.gradient();
+            check(vertex(&|i| a[i] >= T::zero()));
+            check(vertex(&|i| a[i] < T::zero()));
+            2
+        };
+        let mut basic = Basic::from_limits(
+            &format!("{} - WC Analysis", self.name),
+            nominal,
+            lower,
+            upper,
+        );
+        basic.desc = format!("(vertex search over {searched} vertices)");
+        basic
+    }
+
+    /// First order RSS, i.e. `calc::rss` of the linearized stack.
+    pub fn rss(&self) -> Result<Basic<T>> {
+        let terms: Vec<T> =
+            calc::scaled_half_tols(&self.dims.iter().collect::<Vec<_>>(), &self.gradient())
+                .into_iter()
+                .zip(&self.dims)
+                .map(|(t, dim)| dim.basic().nom.x.signum() * t)
+                .collect();
+        let t_rss = calc::correlated_rss(self.correlation()?.as_ref(), &terms);
+        Ok(calc::linearized(&self.name, "RSS", self.nominal(), t_rss))
+    }
+
+    /// Second order RSS of normal inputs at +/- 3 sigma.
+    ///
+    /// The curvature of `f` shifts the mean by `sum(H_ii s_i^2) / 2` and adds
+    /// `sum(H_ij^2 s_i^2 s_j^2) / 2` to the variance of the first order
+    /// result. Dims must be independent.
+    pub fn rss2(&self) -> Result<Basic<T>> {
+        calc::check_independent(self.correlation()?.as_ref(), "Second Order RSS")?;
+        let a = self.gradient();
+        let h = self.hessian();
+        let s = self.std_devs();
+        let half: T = cast(0.5);
+        let shift = half * calc::sum((0..s.len()).map(|i| h[i][i] * s[i] * s[i]));
+        let linear = calc::sum(a.iter().zip(&s).map(|(&a, &s)| (a * s).powi(2)));
+        let quadratic = half
+            * calc::sum(
+                (0..s.len())
+                    .flat_map(|i| (0..s.len()).map(move |j| (i, j)))
+                    .map(|(i, j)| (h[i][j] * s[i] * s[j]).powi(2)),
+            );
+        let mean = self.nominal() + shift;
+        let t = cast::<T>(3.0) * (linear + quadratic).sqrt();
+        let mut basic = Basic::from_limits(
+            &format!("{} - Second Order RSS Analysis", self.name),
+            mean,
+            mean - t,
+            mean + t,
+        );
+        basic.desc = "(assuming inputs with Normal Dist. at +/- 3 sigma)".to_owned();
+        Ok(basic)
+    }
+
+    /// Moments of the gap from a second order Taylor expansion of `f` about
+    /// the mean of every dim, with each dim distributed as in `monte_carlo`.
+    ///
+    /// Each dim contributes `a_i d_i + H_ii d_i^2 / 2` to the gap, whose
+    /// cumulants are summed over the dims. Cross terms `H_ij d_i d_j` only
+    /// add to the variance. Dims must be independent.
+    pub fn moments(&self) -> Result<dist::Moments<T>> {
+        calc::check_independent(self.correlation()?.as_ref(), "Method of Moments")?;
+        let dists: Vec<Option<dist::Distribution<T>>> =
+            self.dims.iter().map(|dim| dim.distribution()).collect();
+        let means: Vec<T> = self
+            .dims
+            .iter()
+            .zip(&dists)
+            .map(|(dim, d)| match d {
+                Some(d) => d.mean(),
+                None => dim.basic().absolute_median(),
+            })
+            .collect();
+        // E[d^k] for k = 0..=8 about the mean of each dim
+        let central: Vec<Vec<T>> = dists
+            .iter()
+            .map(|d| {
+                (0..=8)
+                    .map(|k| match (k, d) {
+                        (0, _) => T::one(),
+                        (_, Some(d)) => d.central_moment(k),
+                        (_, None) => T::zero(),
+                    })
+                    .collect()
+            })
+            .collect();
+        let a = self.gradient_at(&means);
+        let h = self.hessian_at(&means);
+
+        let mut cumulants = [T::zero(); 4];
+        for (i, e) in central.iter().enumerate() {
+            let b = h[i][i] / cast(2.0);
+            // E[g^m] for g = a d + b d^2
+            let raw: Vec<T> = (1..=4)
+                .map(|m: i32| {
+                    calc::sum((0..=m).map(|r| {
+                        cast::<T>(binomial(m, r))
+                            * a[i].powi(r)
+                            * b.powi(m - r)
+                            * e[(2 * m - r) as usize]
+                    }))
+                })
+                .collect();
+            for (c, k) in cumulants.iter_mut().zip(raw_to_cumulants(&raw)) {
+                *c = *c + k;
+            }
+        }
+        for i in 0..central.len() {
+            for j in 0..i {
+                cumulants[1] = cumulants[1] + (h[i][j]).powi(2) * central[i][2] * central[j][2];
+            }
+        }
+        let variance = cumulants[1];
+        Ok(dist::Moments {
+            mean: self.gap(&means) + cumulants[0],
+            variance,
+            skewness: cumulants[2] / variance.powf(cast(1.5)),
+            kurtosis: cumulants[3] / (variance * variance) + cast(3.0),
+        })
+    }
+
+    /// Method of moments analysis: a Pearson distribution fitted to
+    /// `moments`, with limits at the quantiles that a normal distribution
+    /// would have at +/- `at` sigma.
+    ///
+    /// The fitted distribution can be checked against a `Requirement`. Dims
+    /// must be independent.
+    pub fn method_of_moments(&self, at: T) -> Result<Reviewed<T>> {
+        let fit = dist::Tabulated::pearson(&self.moments()?)?;
+        let mut result = calc::summarize(
+            &format!("{} - Method of Moments Analysis", self.name),
+            dist::Distribution::Tabulated(fit),
+            at,
+        )?;
+        result.dim.desc = "(second order, Pearson fit)".to_owned();
+        Ok(result)
+    }
+
+    /// Monte Carlo simulation of `f`, see `calc::monte_carlo`.
+    pub fn monte_carlo(&self, at: T, samples: usize, seed: u64) -> Result<Reviewed<T>> {
+        let dims: Vec<&Contributor<T>> = self.dims.iter().collect();
+        calc::simulate(
+            &self.name,
+            &dims,
+            |x| self.gap(x),
+            self.correlation()?.as_ref(),
+            at,
+            samples,
+            seed,
+        )
+    }
+
+    /// Sobol indices of every dim, see `sobol::indices`.
+    pub fn sobol(&self, samples: usize, seed: u64) -> Result<Vec<sobol::Sobol<T>>> {
+        let dims: Vec<&Contributor<T>> = self.dims.iter().collect();
+        sobol::indices(
+            &dims,
+            |x| self.gap(x),
+            self.correlation()?.as_ref(),
+            samples,
+            seed,
+        )
+    }
+}
+
+/// Finite difference step of `eps^power` relative to the scale of the dim.
+fn step<T: Float>(x: T, half_tol: T, power: T) -> T {
+    let scale = x.abs().max(half_tol);
+    let scale = if scale > T::zero() { scale } else { T::one() };
+    scale * T::epsilon().powf(power)
+}
+
+fn binomial(n: i32, k: i32) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * f64::from(n - i) / f64::from(i + 1))
+}
+
+/// First four cumulants from the first four raw moments.
+fn raw_to_cumulants<T: Float>(m: &[T]) -> [T; 4] {
+    let c = |x: f64| cast::<T>(x);
+    [
+        m[0],
+        m[1] - m[0] * m[0],
+        m[2] - c(3.0) * m[1] * m[0] + c(2.0) * m[0].powi(3),
+        m[3] - c(4.0) * m[2] * m[0] - c(3.0) * m[1] * m[1] + c(12.0) * m[1] * m[0] * m[0]
+            - c(6.0) * m[0].powi(4),
+    ]
+}
+
+fn offset<T: Float>(x: &[T], by: &[(usize, T)]) -> Vec<T> {
+    let mut x = x.to_vec();
+    for &(i, h) in by {
+        x[i] = x[i] + h;
+    }
+    x
+}
+
+pub struct NonlinearStackBuilder<T = f64> {
+    stack: NonlinearStack<T>,
+}
+
+impl<T: Float> NonlinearStackBuilder<T> {
+    pub fn desc(mut self, description: &str) -> NonlinearStackBuilder<T> {
+        self.stack.description = description.to_owned();
+        self
+    }
+
+    pub fn push(mut self, dim: impl Into<Contributor<T>>) -> NonlinearStackBuilder<T> {
+        self.stack.append(dim);
+        self
+    }
+
+    pub fn build(self) -> NonlinearStack<T> {
+        self.stack
+    }
+}
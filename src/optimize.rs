@@ -0,0 +1,158 @@
+use crate::calc;
+use crate::dim::{Contributor, Requirement, Stack};
+use crate::error::{Error, Result};
+use crate::utils::{cast, to_f64, total_cmp};
+
+use num_traits::Float;
+
+/// How far the nominal size of a dim may be moved, e.g. within the range a
+/// supplier can hold or the drawing allows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Range<T = f64> {
+    pub dim: String,
+    /// Largest decrease of the size, zero or negative.
+    pub lower: T,
+    /// Largest increase of the size, zero or positive.
+    pub upper: T,
+}
+
+impl<T: Float> Range<T> {
+    pub fn new(dim: &str, lower: T, upper: T) -> Result<Range<T>> {
+        if lower.is_nan() || upper.is_nan() || lower > T::zero() || upper < T::zero() {
+            return Err(Error::InvertedLimits {
+                lower: to_f64(lower),
+                upper: to_f64(upper),
+            });
+        }
+        Ok(Range {
+            dim: dim.to_owned(),
+            lower,
+            upper,
+        })
+    }
+
+    /// Move the size by up to `shift` either way.
+    pub fn symmetric(dim: &str, shift: T) -> Result<Range<T>> {
+        Range::new(dim, -shift.abs(), shift.abs())
+    }
+}
+
+/// Result of `recenter`.
+pub struct Recentered<T = f64> {
+    /// Change of the nominal size of every dim with a range, in stack order.
+    pub shifts: Vec<(String, T)>,
+    /// The stack with the shifted nominals and distributions.
+    pub stack: Stack<T>,
+    pub yield_before: T,
+    pub yield_after: T,
+}
+
+/// Move the nominals of the dims given `ranges` to maximize the yield of
+/// the stack against `requirement`.
+///
+/// Shifting nominals translates the assembly distribution, found once by
+/// `calc::convolve`, so the best total shift of the gap is found first.
+/// It is then split between the dims with the smallest sum of squared
+/// shifts, each shift growing with the sensitivity of the stack to the dim
+/// until it reaches its range. Shifts are of the size, so a positive shift
+/// of a dim in the negative direction closes the gap.
+///
+/// Dims must be independent.
+pub fn recenter<T: Float>(
+    stack: &Stack<T>,
+    requirement: &Requirement<T>,
+    ranges: &[Range<T>],
+) -> Result<Recentered<T>> {
+    calc::check_independent(stack.correlation()?.as_ref(), "Recenter")?;
+    // gap per unit shift, and bounds, of each dim that may move
+    let mut movable = Vec::with_capacity(ranges.len());
+    for (i, dim) in stack.dims.iter().enumerate() {
+        if let Some(range) = ranges.iter().find(|range| range.dim == dim.name()) {
+            let basic = dim.basic();
+            movable.push((i, basic.nom.x.signum() * basic.a, range.lower, range.upper));
+        }
+    }
+    if let Some(range) = ranges
+        .iter()
+        .find(|range| stack.dims.iter().all(|dim| dim.name() != range.dim))
+    {
+        return Err(Error::UnknownDim(range.dim.clone()));
+    }
+
+    let assembly = calc::convolve(stack, cast(3.0))?
+        .distribution
+        .expect("convolution result has a distribution");
+    let loss = |gap: T| assembly.cdf(requirement.ll - gap) + assembly.sf(requirement.ul - gap);
+
+    // best total shift of the gap, scanned then refined by golden section
+    const SCAN: usize = 200;
+    let lowest = calc::sum(movable.iter().map(|&(_, c, l, u)| (c * l).min(c * u)));
+    let highest = calc::sum(movable.iter().map(|&(_, c, l, u)| (c * l).max(c * u)));
+    let step = (highest - lowest) / cast(SCAN as f64);
+    let at = |k: usize| lowest + step * cast(k as f64);
+    let best = (0..=SCAN)
+        .min_by(|&a, &b| total_cmp(&loss(at(a)), &loss(at(b))))
+        .unwrap();
+    let (mut a, mut b) = (at(best.saturating_sub(1)), at((best + 1).min(SCAN)));
+    let ratio: T = cast((5f64.sqrt() - 1.0) / 2.0);
+    for _ in 0..100 {
+        let (c, d) = (b - (b - a) * ratio, a + (b - a) * ratio);
+        if loss(c) < loss(d) {
+            b = d;
+        } else {
+            a = c;
+        }
+    }
+    let target = (a + b) / cast(2.0);
+    let target = if loss(target) < loss(at(best)) {
+        target
+    } else {
+        at(best)
+    };
+
+    // shifts c_i * lambda clamped to their ranges, with lambda such that
+    // they add up to the target
+    let shifts_at = |lambda: T| -> Vec<T> {
+        movable
+            .iter()
+            .map(|&(_, c, l, u)| (c * lambda).max(l).min(u))
+            .collect()
+    };
+    let total = |shifts: &[T]| calc::sum(movable.iter().zip(shifts).map(|(m, &s)| m.1 * s));
+    let limit = movable
+        .iter()
+        .filter(|m| m.1 != T::zero())
+        .fold(T::zero(), |acc, &(_, c, l, u)| {
+            acc.max(l.abs() / c.abs()).max(u.abs() / c.abs())
+        });
+    let (mut low, mut high) = (-limit, limit);
+    for _ in 0..200 {
+        let mid = (low + high) / cast(2.0);
+        if total(&shifts_at(mid)) < target {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    let shifts = shifts_at((low + high) / cast(2.0));
+
+    let mut recentered = stack.clone();
+    for (&(i, _, _, _), &shift) in movable.iter().zip(&shifts) {
+        let dim = &mut recentered.dims[i];
+        let d = dim.basic().nom.x.signum() * shift;
+        dim.basic_mut().nom.x = dim.basic().nom.x + d;
+        if let Contributor::Reviewed(reviewed) = dim {
+            reviewed.distribution = reviewed.distribution.as_ref().map(|x| x.shifted(d));
+        }
+    }
+    Ok(Recentered {
+        shifts: movable
+            .iter()
+            .zip(&shifts)
+            .map(|(&(i, _, _, _), &shift)| (stack.dims[i].name().to_owned(), shift))
+            .collect(),
+        stack: recentered,
+        yield_before: T::one() - loss(T::zero()),
+        yield_after: T::one() - loss(total(&shifts)),
+    })
+}
@@ -0,0 +1,72 @@
+use crate::calc;
+use crate::dim::Stack;
+use crate::error::Result;
+
+use num_traits::Float;
+
+/// A part dimensions are on, identified by its part number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Part {
+    pub number: String,
+    pub name: String,
+    pub revision: String,
+    pub supplier: String,
+}
+
+impl Part {
+    pub fn new(number: &str, name: &str) -> Part {
+        Part {
+            number: number.to_owned(),
+            name: name.to_owned(),
+            revision: "".to_owned(),
+            supplier: "".to_owned(),
+        }
+    }
+
+    pub fn revision(mut self, revision: &str) -> Part {
+        self.revision = revision.to_owned();
+        self
+    }
+
+    pub fn supplier(mut self, supplier: &str) -> Part {
+        self.supplier = supplier.to_owned();
+        self
+    }
+}
+
+/// Contributions of the dims on one part, see `calc::contributions`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartShare<T = f64> {
+    /// The part, or `None` for the dims not on any part.
+    pub part: Option<Part>,
+    /// Names of the dims on the part, in stack order.
+    pub dims: Vec<String>,
+    pub wc: T,
+    pub rss: T,
+}
+
+/// Contributions to the stack summed by part number, in the order parts
+/// first appear in the stack.
+pub fn by_part<T: Float>(stack: &Stack<T>) -> Result<Vec<PartShare<T>>> {
+    let mut shares: Vec<PartShare<T>> = Vec::new();
+    for contribution in calc::contributions(stack)? {
+        let number = contribution.part.as_ref().map(|part| &part.number);
+        match shares
+            .iter_mut()
+            .find(|share| share.part.as_ref().map(|part| &part.number) == number)
+        {
+            Some(share) => {
+                share.dims.push(contribution.name);
+                share.wc = share.wc + contribution.wc;
+                share.rss = share.rss + contribution.rss;
+            }
+            None => shares.push(PartShare {
+                part: contribution.part,
+                dims: vec![contribution.name],
+                wc: contribution.wc,
+                rss: contribution.rss,
+            }),
+        }
+    }
+    Ok(shares)
+}
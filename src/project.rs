@@ -0,0 +1,228 @@
+use crate::dim::{Contributor, Stack};
+use crate::error::{Error, Result};
+
+use num_traits::Float;
+
+/// A use of a project dim in a stack.
+#[derive(Clone)]
+pub struct Entry<T = f64> {
+    /// Name of the dim in the project.
+    pub dim: String,
+    /// Whether the dim runs in the negative direction of this stack.
+    pub reversed: bool,
+    /// Sensitivity of this stack to the dim, on top of the dim's own.
+    pub a: T,
+}
+
+impl<T: Float> Entry<T> {
+    pub fn new(dim: &str) -> Entry<T> {
+        Entry {
+            dim: dim.to_owned(),
+            reversed: false,
+            a: T::one(),
+        }
+    }
+
+    pub fn reversed(mut self) -> Entry<T> {
+        self.reversed = !self.reversed;
+        self
+    }
+
+    pub fn sensitivity(mut self, a: T) -> Entry<T> {
+        self.a = a;
+        self
+    }
+}
+
+/// A stack made of dims owned by a `Project`, referenced by name.
+#[derive(Clone)]
+pub struct StackRef<T = f64> {
+    pub name: String,
+    pub description: String,
+    pub entries: Vec<Entry<T>>,
+    /// Correlations between dims by name, see `Stack::correlate`.
+    pub correlations: Vec<(String, String, T)>,
+}
+
+impl<T: Float> StackRef<T> {
+    pub fn builder(name: &str) -> StackRefBuilder<T> {
+        StackRefBuilder {
+            stack: StackRef {
+                name: name.to_owned(),
+                description: "".to_owned(),
+                entries: Vec::new(),
+                correlations: Vec::new(),
+            },
+        }
+    }
+}
+
+pub struct StackRefBuilder<T = f64> {
+    stack: StackRef<T>,
+}
+
+impl<T: Float> StackRefBuilder<T> {
+    pub fn desc(mut self, description: &str) -> StackRefBuilder<T> {
+        self.stack.description = description.to_owned();
+        self
+    }
+
+    /// Use the named dim as it is defined in the project.
+    pub fn push(self, dim: &str) -> StackRefBuilder<T> {
+        self.entry(Entry::new(dim))
+    }
+
+    /// Use the named dim in the negative direction of this stack.
+    pub fn push_reversed(self, dim: &str) -> StackRefBuilder<T> {
+        self.entry(Entry::new(dim).reversed())
+    }
+
+    pub fn entry(mut self, entry: Entry<T>) -> StackRefBuilder<T> {
+        self.stack.entries.push(entry);
+        self
+    }
+
+    pub fn correlate(mut self, a: &str, b: &str, rho: T) -> StackRefBuilder<T> {
+        self.stack
+            .correlations
+            .push((a.to_owned(), b.to_owned(), rho));
+        self
+    }
+
+    pub fn build(self) -> StackRef<T> {
+        self.stack
+    }
+}
+
+/// A library of named dims shared by many stacks.
+///
+/// Stacks refer to dims by name and are resolved into `Stack`s when
+/// analyzed, so editing a dim once updates every stack using it.
+pub struct Project<T = f64> {
+    pub name: String,
+    dims: Vec<Contributor<T>>,
+    stacks: Vec<StackRef<T>>,
+}
+
+impl<T: Float> Project<T> {
+    pub fn new(name: &str) -> Project<T> {
+        Project {
+            name: name.to_owned(),
+            dims: Vec::new(),
+            stacks: Vec::new(),
+        }
+    }
+
+    /// Add a dim, either a `Basic` or a `Reviewed`. Names must be unique.
+    pub fn add_dim(&mut self, dim: impl Into<Contributor<T>>) -> Result<()> {
+        let dim = dim.into();
+        if self.dim(dim.name()).is_some() {
+            return Err(Error::DuplicateDim(dim.name().to_owned()));
+        }
+        self.dims.push(dim);
+        Ok(())
+    }
+
+    /// Replace the dim of the same name, e.g. after a tolerance change.
+    pub fn update_dim(&mut self, dim: impl Into<Contributor<T>>) -> Result<()> {
+        let dim = dim.into();
+        let existing = self
+            .dims
+            .iter_mut()
+            .find(|d| d.name() == dim.name())
+            .ok_or_else(|| Error::UnknownDim(dim.name().to_owned()))?;
+        *existing = dim;
+        Ok(())
+    }
+
+    pub fn dim(&self, name: &str) -> Option<&Contributor<T>> {
+        self.dims.iter().find(|dim| dim.name() == name)
+    }
+
+    pub fn dim_mut(&mut self, name: &str) -> Option<&mut Contributor<T>> {
+        self.dims.iter_mut().find(|dim| dim.name() == name)
+    }
+
+    pub fn dims(&self) -> &[Contributor<T>] {
+        &self.dims
+    }
+
+    /// Add a stack. Every dim it uses must already be in the project.
+    pub fn add_stack(&mut self, stack: StackRef<T>) -> Result<()> {
+        for entry in &stack.entries {
+            if self.dim(&entry.dim).is_none() {
+                return Err(Error::UnknownDim(entry.dim.clone()));
+            }
+        }
+        self.stacks.push(stack);
+        Ok(())
+    }
+
+    pub fn stack_refs(&self) -> &[StackRef<T>] {
+        &self.stacks
+    }
+
+    /// Names of the stacks using the named dim.
+    pub fn users(&self, dim: &str) -> Vec<&str> {
+        self.stacks
+            .iter()
+            .filter(|stack| stack.entries.iter().any(|entry| entry.dim == dim))
+            .map(|stack| stack.name.as_str())
+            .collect()
+    }
+
+    /// The named stack, with the current value of every dim.
+    pub fn stack(&self, name: &str) -> Result<Stack<T>> {
+        let stack = self
+            .stacks
+            .iter()
+            .find(|stack| stack.name == name)
+            .ok_or_else(|| Error::UnknownStack(name.to_owned()))?;
+        self.resolve(stack)
+    }
+
+    /// Every stack, with the current value of every dim.
+    pub fn stacks(&self) -> Result<Vec<Stack<T>>> {
+        self.stacks
+            .iter()
+            .map(|stack| self.resolve(stack))
+            .collect()
+    }
+
+    /// Run `analysis` on every stack, e.g. `project.run(calc::wc)`.
+    pub fn run<R>(&self, analysis: impl Fn(&Stack<T>) -> R) -> Result<Vec<R>> {
+        Ok(self.stacks()?.iter().map(analysis).collect())
+    }
+
+    fn resolve(&self, stack: &StackRef<T>) -> Result<Stack<T>> {
+        let mut dims = Vec::with_capacity(stack.entries.len());
+        for entry in &stack.entries {
+            let mut dim = self
+                .dim(&entry.dim)
+                .ok_or_else(|| Error::UnknownDim(entry.dim.clone()))?
+                .clone();
+            if entry.reversed {
+                dim = reverse(dim);
+            }
+            let basic = dim.basic_mut();
+            basic.a = basic.a * entry.a;
+            dims.push(dim);
+        }
+        let mut resolved = Stack::new(stack.name.clone(), stack.description.clone(), dims);
+        for (a, b, rho) in &stack.correlations {
+            resolved.correlate(a, b, *rho)?;
+        }
+        Ok(resolved)
+    }
+}
+
+/// The dim measured in the opposite direction. The tolerance is relative to
+/// the size, so only the sign of the nominal and the distribution change.
+fn reverse<T: Float>(mut dim: Contributor<T>) -> Contributor<T> {
+    let basic = dim.basic_mut();
+    basic.nom.x = -basic.nom.x;
+    if let Contributor::Reviewed(reviewed) = &mut dim {
+        reviewed.distribution = reviewed.distribution.as_ref().map(|d| d.scaled(-T::one()));
+    }
+    dim
+}
@@ -0,0 +1,300 @@
+use crate::calc;
+use crate::dim::{Basic, Contributor, Requirement, Reviewed, Stack};
+use crate::dist::Distribution;
+use crate::error::{Error, Result};
+use crate::tol::Bilateral;
+use crate::utils::{cast, to_f64};
+
+use num_traits::Float;
+use std::fmt;
+
+/// A change to one dim of a stack.
+#[derive(Clone)]
+pub enum Change<T = f64> {
+    /// Move the nominal, and the distribution of a reviewed dim with it.
+    Nominal(T),
+    Tolerance(Bilateral<T>),
+    Distribution(Distribution<T>),
+    /// Review the dim at a target process sigma, assuming it normal.
+    ProcessSigma(T),
+}
+
+/// A variant of a stack, as a list of changes to its dims by name.
+///
+/// Changes apply in order. A reviewed dim with a normal distribution is
+/// taken to have been assumed from its limits, so it is assumed again from
+/// its new tolerance; other distributions are kept as measured.
+#[derive(Clone)]
+pub struct Scenario<T = f64> {
+    pub name: String,
+    pub changes: Vec<(String, Change<T>)>,
+}
+
+impl<T: Float> Scenario<T> {
+    pub fn new(name: &str) -> Scenario<T> {
+        Scenario {
+            name: name.to_owned(),
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn nominal(mut self, dim: &str, nom: T) -> Scenario<T> {
+        self.changes.push((dim.to_owned(), Change::Nominal(nom)));
+        self
+    }
+
+    pub fn tolerance(mut self, dim: &str, tol: Bilateral<T>) -> Scenario<T> {
+        self.changes.push((dim.to_owned(), Change::Tolerance(tol)));
+        self
+    }
+
+    pub fn distribution(mut self, dim: &str, distribution: Distribution<T>) -> Scenario<T> {
+        self.changes
+            .push((dim.to_owned(), Change::Distribution(distribution)));
+        self
+    }
+
+    pub fn process_sigma(mut self, dim: &str, target_process_sigma: T) -> Scenario<T> {
+        self.changes
+            .push((dim.to_owned(), Change::ProcessSigma(target_process_sigma)));
+        self
+    }
+
+    /// The stack with the changes applied.
+    pub fn apply(&self, stack: &Stack<T>) -> Result<Stack<T>> {
+        let mut variant = stack.clone();
+        variant.name = format!("{} ({})", stack.name, self.name);
+        for (name, change) in &self.changes {
+            let dim = variant
+                .dims
+                .iter_mut()
+                .find(|dim| dim.name() == name)
+                .ok_or_else(|| Error::UnknownDim(name.clone()))?;
+            *dim = match (dim.clone(), change) {
+                (mut dim, Change::Nominal(nom)) => {
+                    let shift = *nom - dim.basic().nom.x;
+                    dim.basic_mut().nom.x = *nom;
+                    if let Contributor::Reviewed(reviewed) = &mut dim {
+                        reviewed.distribution =
+                            reviewed.distribution.as_ref().map(|d| d.shifted(shift));
+                    }
+                    dim
+                }
+                (Contributor::Basic(mut basic), Change::Tolerance(tol)) => {
+                    basic.tol = tol.clone();
+                    Contributor::Basic(basic)
+                }
+                (Contributor::Reviewed(mut reviewed), Change::Tolerance(tol)) => {
+                    reviewed.dim.tol = tol.clone();
+                    match reviewed.distribution {
+                        Some(Distribution::Normal(_)) => {
+                            Reviewed::of_basic(reviewed.dim, reviewed.target_process_sigma)?.into()
+                        }
+                        _ => reviewed.into(),
+                    }
+                }
+                (dim, Change::Distribution(distribution)) => Reviewed::new(
+                    dim.basic().clone(),
+                    target_process_sigma(&dim),
+                    Some(distribution.clone()),
+                )?
+                .into(),
+                (dim, Change::ProcessSigma(sigma)) => {
+                    Reviewed::of_basic(dim.basic().clone(), *sigma)?.into()
+                }
+            };
+        }
+        Ok(variant)
+    }
+}
+
+fn target_process_sigma<T: Float>(dim: &Contributor<T>) -> T {
+    dim.reviewed()
+        .map(|reviewed| reviewed.target_process_sigma)
+        .unwrap_or_else(|| cast(3.0))
+}
+
+/// Results of the analyses of one variant of a stack.
+pub struct Outcome<T = f64> {
+    pub name: String,
+    pub closed: Basic<T>,
+    pub wc: Basic<T>,
+    pub rss: Basic<T>,
+    /// `None` for a stack of fewer than two dims.
+    pub mrss: Option<Basic<T>>,
+    /// `None` unless every dim is reviewed.
+    pub six_sigma: Option<Reviewed<T>>,
+    /// Yield against the requirement from `calc::convolve`, `None` for a
+    /// stack of correlated dims.
+    pub yield_probability: Option<T>,
+}
+
+impl<T: Float> Outcome<T> {
+    pub(crate) fn of(
+        name: &str,
+        stack: &Stack<T>,
+        requirement: &Requirement<T>,
+        at: T,
+    ) -> Result<Outcome<T>> {
+        let yield_probability = calc::convolve(stack, at).ok().and_then(|result| {
+            result.distribution.map(|distribution| {
+                T::one() - distribution.cdf(requirement.ll) - distribution.sf(requirement.ul)
+            })
+        });
+        Ok(Outcome {
+            name: name.to_owned(),
+            closed: calc::closed(stack),
+            wc: calc::wc(stack),
+            rss: calc::rss(stack)?,
+            mrss: calc::mrss(stack).ok(),
+            six_sigma: calc::six_sigma(stack, at).ok(),
+            yield_probability,
+        })
+    }
+
+    /// Rejects per million against the requirement.
+    pub fn reject_ppm(&self) -> Option<T> {
+        self.yield_probability
+            .map(|p| (T::one() - p) * cast(1_000_000.0))
+    }
+
+    /// The total tolerance of each analysis, in the order of the table.
+    fn tolerances(&self) -> [Option<T>; 5] {
+        [
+            Some(self.closed.tol.t()),
+            Some(self.wc.tol.t()),
+            Some(self.rss.tol.t()),
+            self.mrss.as_ref().map(|mrss| mrss.tol.t()),
+            self.six_sigma.as_ref().map(|result| result.dim.tol.t()),
+        ]
+    }
+}
+
+/// Change from the baseline to a scenario, in total tolerance of each
+/// analysis and in yield. Negative tolerance deltas are improvements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delta<T = f64> {
+    pub name: String,
+    pub closed: T,
+    pub wc: T,
+    pub rss: T,
+    pub mrss: Option<T>,
+    pub six_sigma: Option<T>,
+    pub yield_probability: Option<T>,
+}
+
+/// Side by side results of a stack and its scenarios.
+pub struct Comparison<T = f64> {
+    pub baseline: Outcome<T>,
+    pub scenarios: Vec<Outcome<T>>,
+}
+
+impl<T: Float> Comparison<T> {
+    /// Run every analysis on the stack and on each scenario of it, with
+    /// statistical results at +/- `at` sigma.
+    pub fn new(
+        stack: &Stack<T>,
+        scenarios: &[Scenario<T>],
+        requirement: &Requirement<T>,
+        at: T,
+    ) -> Result<Comparison<T>> {
+        let baseline = Outcome::of("baseline", stack, requirement, at)?;
+        let scenarios = scenarios
+            .iter()
+            .map(|scenario| {
+                let variant = scenario.apply(stack)?;
+                Outcome::of(&scenario.name, &variant, requirement, at)
+            })
+            .collect::<Result<Vec<Outcome<T>>>>()?;
+        Ok(Comparison {
+            baseline,
+            scenarios,
+        })
+    }
+
+    /// Change from the baseline to each scenario.
+    pub fn deltas(&self) -> Vec<Delta<T>> {
+        let delta = |a: Option<T>, b: Option<T>| Some(b? - a?);
+        let base = self.baseline.tolerances();
+        self.scenarios
+            .iter()
+            .map(|outcome| {
+                let t = outcome.tolerances();
+                Delta {
+                    name: outcome.name.clone(),
+                    closed: t[0].unwrap() - base[0].unwrap(),
+                    wc: t[1].unwrap() - base[1].unwrap(),
+                    rss: t[2].unwrap() - base[2].unwrap(),
+                    mrss: delta(base[3], t[3]),
+                    six_sigma: delta(base[4], t[4]),
+                    yield_probability: delta(
+                        self.baseline.yield_probability,
+                        outcome.yield_probability,
+                    ),
+                }
+            })
+            .collect()
+    }
+}
+
+/// A table of the total tolerance of each analysis and the reject PPM, with
+/// one column per variant and the change from the baseline in brackets.
+impl<T: Float> fmt::Display for Comparison<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcomes: Vec<&Outcome<T>> = std::iter::once(&self.baseline)
+            .chain(self.scenarios.iter())
+            .collect();
+        let cell = |value: Option<T>, base: Option<T>, first: bool| match (value, base) {
+            (None, _) => "-".to_owned(),
+            (Some(v), Some(b)) if !first => {
+                format!("{:.4} ({:+.4})", to_f64(v), to_f64(v - b))
+            }
+            (Some(v), _) => format!("{:.4}", to_f64(v)),
+        };
+        let labels = ["Closed", "WC", "RSS", "MRSS", "6 Sigma", "Reject PPM"];
+        let rows: Vec<Vec<String>> = labels
+            .iter()
+            .enumerate()
+            .map(|(row, _)| {
+                outcomes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, outcome)| {
+                        let value = |o: &Outcome<T>| {
+                            if row < 5 {
+                                o.tolerances()[row]
+                            } else {
+                                o.reject_ppm()
+                            }
+                        };
+                        cell(value(outcome), value(&self.baseline), i == 0)
+                    })
+                    .collect()
+            })
+            .collect();
+        let widths: Vec<usize> = outcomes
+            .iter()
+            .enumerate()
+            .map(|(i, outcome)| {
+                rows.iter()
+                    .map(|row| row[i].len())
+                    .max()
+                    .unwrap_or(0)
+                    .max(outcome.name.len())
+            })
+            .collect();
+        write!(f, "{:<10}", "")?;
+        for (outcome, width) in outcomes.iter().zip(&widths) {
+            write!(f, "  {:>width$}", outcome.name)?;
+        }
+        writeln!(f)?;
+        for (label, row) in labels.iter().zip(&rows) {
+            write!(f, "{label:<10}")?;
+            for (value, width) in row.iter().zip(&widths) {
+                write!(f, "  {value:>width$}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
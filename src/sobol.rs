@@ -0,0 +1,150 @@
+use crate::calc::sum;
+use crate::correlation::Correlation;
+use crate::dim::Contributor;
+use crate::dist;
+use crate::error::{Error, Result};
+use crate::utils::cast;
+
+use num_traits::Float;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Variance based sensitivity of a gap to one dim.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sobol<T = f64> {
+    pub name: String,
+    /// Fraction of the variance of the gap explained by the dim alone,
+    /// `Var(E[gap | dim]) / Var(gap)`.
+    pub first_order: T,
+    /// Fraction of the variance of the gap left when every other dim is
+    /// known, `E[Var(gap | other dims)] / Var(gap)`, which adds the dim's
+    /// interactions with the others.
+    pub total: T,
+}
+
+/// First order and total Sobol indices of `f` of the absolute values of
+/// `dims`, by sampling their distributions, see `calc::monte_carlo`.
+///
+/// Each index needs pairs of samples that share either the dim or every
+/// other dim. With correlated dims, the rest of a pair is drawn from its
+/// distribution given the shared part, through the normal copula, so the
+/// first order index of a dim includes what it explains through the dims
+/// it is correlated with, and the total index excludes it (Kucherenko et
+/// al., 2012). For independent dims these are the usual Saltelli and Jansen
+/// estimators. The indices of a linear stack of independent dims are their
+/// RSS variance shares, see `calc::contributions`.
+///
+/// Costs up to `samples * (4 n + 1)` evaluations of `f` for `n` dims.
+pub fn indices<T: Float>(
+    dims: &[&Contributor<T>],
+    f: impl Fn(&[T]) -> T,
+    correlation: Option<&Correlation<T>>,
+    samples: usize,
+    seed: u64,
+) -> Result<Vec<Sobol<T>>> {
+    let n = dims.len();
+    if n == 0 {
+        return Err(Error::TooFewDims {
+            analysis: "Sobol",
+            required: 1,
+            found: 0,
+        });
+    }
+    if samples < 2 {
+        return Err(Error::EmptyInput);
+    }
+    let matrix = match correlation {
+        Some(correlation) => correlation.matrix(n),
+        None => Correlation::identity(n).matrix(n),
+    };
+    let dists: Vec<Option<dist::Distribution<T>>> =
+        dims.iter().map(|dim| dim.distribution()).collect();
+    let standard = dist::Normal::new(T::zero(), T::one())?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut normals = |count: usize| -> Vec<T> {
+        (0..count)
+            .map(|_| standard.quantile(cast(rng.r#gen::<f64>())))
+            .collect()
+    };
+    // correlated normal scores to the sizes of the dims, flipping dims in
+    // the negative direction as `calc::simulate` does
+    let gap = |z: &[T]| -> T {
+        let x: Vec<T> = dims
+            .iter()
+            .zip(&dists)
+            .zip(z)
+            .map(|((dim, d), &z)| match d {
+                Some(d) => d.quantile(standard.cdf(dim.basic().nom.x.signum() * z)),
+                None => dim.basic().absolute_median(),
+            })
+            .collect();
+        f(&x)
+    };
+    // scores in the original order from `u` in the order of `order`
+    let scores = |l: &[Vec<T>], order: &[usize], u: &[T]| -> Vec<T> {
+        let mut z = vec![T::zero(); n];
+        for (row, &i) in order.iter().enumerate() {
+            z[i] = sum((0..=row).map(|k| l[row][k] * u[k]));
+        }
+        z
+    };
+    let cholesky = |order: &[usize]| -> Result<Vec<Vec<T>>> {
+        let permuted = order
+            .iter()
+            .map(|&i| order.iter().map(|&j| matrix[i][j]).collect())
+            .collect();
+        Correlation::new(permuted)?.cholesky(n)
+    };
+
+    let natural: Vec<usize> = (0..n).collect();
+    let l = cholesky(&natural)?;
+    let base: Vec<T> = (0..samples)
+        .map(|_| gap(&scores(&l, &natural, &normals(n))))
+        .collect();
+    let count: T = cast(samples as f64);
+    let mean = sum(base.iter().copied()) / count;
+    let variance = sum(base.iter().map(|&y| (y - mean) * (y - mean))) / (count - T::one());
+
+    let mut result = Vec::with_capacity(n);
+    for i in 0..n {
+        if dists[i].is_none() || variance <= T::zero() {
+            // a constant, or nothing varies
+            result.push(Sobol {
+                name: dims[i].name().to_owned(),
+                first_order: T::zero(),
+                total: T::zero(),
+            });
+            continue;
+        }
+        let others = natural.iter().copied().filter(|&j| j != i);
+        // the dim first, so that redrawing the rest keeps it
+        let first: Vec<usize> = std::iter::once(i).chain(others.clone()).collect();
+        let l = cholesky(&first)?;
+        let mut first_order = T::zero();
+        for &y in &base {
+            let u = normals(n);
+            let mut v = normals(n);
+            v[0] = u[0];
+            // centered, or the mean swamps the product
+            let shared = gap(&scores(&l, &first, &u)) - mean;
+            first_order = first_order + shared * (gap(&scores(&l, &first, &v)) - y);
+        }
+        // the dim last, so that redrawing it keeps the rest
+        let last: Vec<usize> = others.chain(std::iter::once(i)).collect();
+        let l = cholesky(&last)?;
+        let mut total = T::zero();
+        for _ in 0..samples {
+            let u = normals(n);
+            let mut v = u.clone();
+            v[n - 1] = normals(1)[0];
+            let d = gap(&scores(&l, &last, &u)) - gap(&scores(&l, &last, &v));
+            total = total + d * d;
+        }
+        result.push(Sobol {
+            name: dims[i].name().to_owned(),
+            first_order: first_order / count / variance,
+            total: total / (count + count) / variance,
+        });
+    }
+    Ok(result)
+}
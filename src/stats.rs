@@ -1,3 +1,6 @@
+use crate::utils::cast;
+use num_traits::Float;
+
 /// Process capability index.
 ///
 /// # Arguments
@@ -19,8 +22,8 @@
 /// assert_eq!(res, 2.0);
 /// ```
 ///
-pub fn c_p(ul: f32, ll: f32, std_dev: f32) -> f32 {
-    (ul - ll) / (6.0 * std_dev)
+pub fn c_p<T: Float>(ul: T, ll: T, std_dev: T) -> T {
+    (ul - ll) / (cast::<T>(6.0) * std_dev)
 }
 
 /// Process capability index. adjusted for centering.
@@ -40,12 +43,13 @@ pub fn c_p(ul: f32, ll: f32, std_dev: f32) -> f32 {
 ///
 /// ```rust
 /// use dimstack_rs::stats::c_pk;
-/// let res = c_pk(208.036, 207.964, 208.009, 0.006);
+/// let res: f64 = c_pk(208.036, 207.964, 208.009, 0.006);
 /// assert_eq!((res * 100.0).round() / 100.0, 1.5); // rounded to 2 decimal places
 /// ```
 ///
-pub fn c_pk(ul: f32, ll: f32, mean: f32, std_dev: f32) -> f32 {
-    f32::min((ul - mean) / (3.0 * std_dev), (mean - ll) / (3.0 * std_dev))
+pub fn c_pk<T: Float>(ul: T, ll: T, mean: T, std_dev: T) -> T {
+    let three_sd = cast::<T>(3.0) * std_dev;
+    T::min((ul - mean) / three_sd, (mean - ll) / three_sd)
 }
 
 /// Root sum square.
@@ -63,10 +67,10 @@ pub fn c_pk(ul: f32, ll: f32, mean: f32, std_dev: f32) -> f32 {
 /// assert_eq!(result, 3.7416573867739413);
 /// ```
 ///
-pub fn rss(args: &Vec<f32>) -> f32 {
-    let mut val = 0.0;
-    for arg in args {
-        val += arg * arg;
+pub fn rss<T: Float>(args: &[T]) -> T {
+    let mut val = T::zero();
+    for &arg in args {
+        val = val + arg * arg;
     }
     val.sqrt()
 }
@@ -82,8 +86,8 @@ pub fn rss(args: &Vec<f32>) -> f32 {
 /// # Example
 ///
 /// TODO
-pub fn c_f(t_rss: f32, t_wc: f32, n: f32) -> f32 {
-    ((0.5 * (t_wc - t_rss)) / (t_rss * (n.sqrt() - 1.0))) + 1.0
+pub fn c_f<T: Float>(t_rss: T, t_wc: T, n: T) -> T {
+    ((cast::<T>(0.5) * (t_wc - t_rss)) / (t_rss * (n.sqrt() - T::one()))) + T::one()
 }
 
 #[cfg(test)]
@@ -0,0 +1,276 @@
+use crate::dim::{Requirement, Stack};
+use crate::error::{Error, Result};
+use crate::scenario::{Outcome, Scenario};
+use crate::tol::Bilateral;
+use crate::utils::{cast, to_f64};
+
+use num_traits::Float;
+use std::fmt;
+
+/// The property of a dim varied by a `Sweep`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Parameter {
+    /// Symmetric tolerance, the value is the half tolerance.
+    Tolerance,
+    Nominal,
+    TargetProcessSigma,
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Parameter::Tolerance => "tolerance",
+            Parameter::Nominal => "nominal",
+            Parameter::TargetProcessSigma => "target process sigma",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Values of one parameter of one dim to run a stack at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep<T = f64> {
+    pub dim: String,
+    pub parameter: Parameter,
+    pub values: Vec<T>,
+}
+
+impl<T: Float> Sweep<T> {
+    pub fn new(dim: &str, parameter: Parameter, values: Vec<T>) -> Sweep<T> {
+        Sweep {
+            dim: dim.to_owned(),
+            parameter,
+            values,
+        }
+    }
+
+    /// `steps` evenly spaced values from `from` to `to`, both included.
+    pub fn linear(dim: &str, parameter: Parameter, from: T, to: T, steps: usize) -> Sweep<T> {
+        let values = match steps {
+            0 => Vec::new(),
+            1 => vec![from],
+            _ => (0..steps)
+                .map(|i| from + (to - from) * cast(i as f64 / (steps - 1) as f64))
+                .collect(),
+        };
+        Sweep::new(dim, parameter, values)
+    }
+
+    /// The variant of a stack at one value, see `Scenario`.
+    pub fn scenario(&self, value: T) -> Scenario<T> {
+        let name = format!("{} {} {}", self.dim, self.parameter, to_f64(value));
+        let scenario = Scenario::new(&name);
+        match self.parameter {
+            Parameter::Tolerance => scenario.tolerance(&self.dim, Bilateral::symmetric(value)),
+            Parameter::Nominal => scenario.nominal(&self.dim, value),
+            Parameter::TargetProcessSigma => scenario.process_sigma(&self.dim, value),
+        }
+    }
+
+    /// Run every analysis on the stack at each value, with statistical
+    /// results at +/- `at` sigma and reject PPM against `requirement`.
+    pub fn run(
+        &self,
+        stack: &Stack<T>,
+        requirement: &Requirement<T>,
+        at: T,
+    ) -> Result<SweepResult<T>> {
+        if self.values.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+        let points = self
+            .values
+            .iter()
+            .map(|&value| {
+                let scenario = self.scenario(value);
+                let variant = scenario.apply(stack)?;
+                Ok((
+                    value,
+                    Outcome::of(&scenario.name, &variant, requirement, at)?,
+                ))
+            })
+            .collect::<Result<Vec<(T, Outcome<T>)>>>()?;
+        Ok(SweepResult {
+            dim: self.dim.clone(),
+            parameter: self.parameter,
+            points,
+        })
+    }
+}
+
+/// Results of a `Sweep`, one point per value.
+pub struct SweepResult<T = f64> {
+    pub dim: String,
+    pub parameter: Parameter,
+    pub points: Vec<(T, Outcome<T>)>,
+}
+
+impl<T: Float> SweepResult<T> {
+    /// Reject PPM against the requirement at each value that has one.
+    pub fn reject_ppm(&self) -> Vec<(T, T)> {
+        self.points
+            .iter()
+            .filter_map(|(value, outcome)| outcome.reject_ppm().map(|ppm| (*value, ppm)))
+            .collect()
+    }
+
+    /// Reject PPM against the swept value as an SVG line chart, with PPM on
+    /// a log scale from 0.001.
+    pub fn svg(&self) -> String {
+        const WIDTH: f64 = 640.0;
+        const HEIGHT: f64 = 400.0;
+        const LEFT: f64 = 70.0;
+        const RIGHT: f64 = 20.0;
+        const TOP: f64 = 40.0;
+        const BOTTOM: f64 = 50.0;
+        let points: Vec<(f64, f64)> = self
+            .reject_ppm()
+            .into_iter()
+            .map(|(value, ppm)| (to_f64(value), to_f64(ppm).max(1e-3).log10()))
+            .collect();
+        let bounds = |v: &mut dyn Iterator<Item = f64>| {
+            v.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), x| {
+                (lo.min(x), hi.max(x))
+            })
+        };
+        let (mut x_min, mut x_max) = bounds(&mut points.iter().map(|p| p.0));
+        if points.is_empty() {
+            (x_min, x_max) = (0.0, 1.0);
+        } else if x_max <= x_min {
+            (x_min, x_max) = (x_min - 1.0, x_min + 1.0);
+        }
+        let (y_min, y_max) = bounds(&mut points.iter().map(|p| p.1));
+        let (y_min, y_max) = if points.is_empty() {
+            (-3.0, 6.0)
+        } else {
+            (y_min.floor(), y_max.ceil().max(y_min.floor() + 1.0))
+        };
+        let px = |x: f64| LEFT + (x - x_min) / (x_max - x_min) * (WIDTH - LEFT - RIGHT);
+        let py = |y: f64| HEIGHT - BOTTOM - (y - y_min) / (y_max - y_min) * (HEIGHT - TOP - BOTTOM);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+             viewBox=\"0 0 {WIDTH} {HEIGHT}\" font-family=\"sans-serif\" font-size=\"12\">\n"
+        );
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\">{} {} sweep</text>\n",
+            WIDTH / 2.0,
+            TOP / 2.0,
+            escape(&self.dim),
+            self.parameter
+        );
+        svg += &format!(
+            "<path d=\"M{LEFT} {TOP}V{}H{}\" fill=\"none\" stroke=\"black\"/>\n",
+            HEIGHT - BOTTOM,
+            WIDTH - RIGHT
+        );
+        for k in 0..=4 {
+            let x = x_min + (x_max - x_min) * k as f64 / 4.0;
+            svg += &format!(
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n",
+                px(x),
+                HEIGHT - BOTTOM + 18.0,
+                format_tick(x)
+            );
+        }
+        for decade in (y_min as i32)..=(y_max as i32) {
+            let y = py(decade as f64);
+            svg += &format!(
+                "<path d=\"M{LEFT} {y:.1}H{}\" stroke=\"#ddd\"/>\n\
+                 <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">1e{decade}</text>\n",
+                WIDTH - RIGHT,
+                LEFT - 6.0,
+                y + 4.0
+            );
+        }
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n\
+             <text x=\"16\" y=\"{}\" text-anchor=\"middle\" \
+             transform=\"rotate(-90 16 {})\">Reject PPM</text>\n",
+            (LEFT + WIDTH - RIGHT) / 2.0,
+            HEIGHT - 12.0,
+            self.parameter,
+            (TOP + HEIGHT - BOTTOM) / 2.0,
+            (TOP + HEIGHT - BOTTOM) / 2.0
+        );
+        let line: Vec<String> = points
+            .iter()
+            .map(|&(x, y)| format!("{:.1},{:.1}", px(x), py(y)))
+            .collect();
+        svg += &format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\"/>\n",
+            line.join(" ")
+        );
+        for &(x, y) in &points {
+            svg += &format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"steelblue\"/>\n",
+                px(x),
+                py(y)
+            );
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+fn format_tick(x: f64) -> String {
+    let s = format!("{x:.4}");
+    s.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// A table of the total tolerance of each analysis and the reject PPM, one
+/// row per value.
+impl<T: Float> fmt::Display for SweepResult<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell = |value: Option<T>| match value {
+            Some(v) => format!("{:.4}", to_f64(v)),
+            None => "-".to_owned(),
+        };
+        let header = [
+            self.parameter.to_string(),
+            "WC".to_owned(),
+            "RSS".to_owned(),
+            "MRSS".to_owned(),
+            "6 Sigma".to_owned(),
+            "Reject PPM".to_owned(),
+        ];
+        let rows: Vec<[String; 6]> = self
+            .points
+            .iter()
+            .map(|(value, outcome)| {
+                [
+                    format_tick(to_f64(*value)),
+                    cell(Some(outcome.wc.tol.t())),
+                    cell(Some(outcome.rss.tol.t())),
+                    cell(outcome.mrss.as_ref().map(|mrss| mrss.tol.t())),
+                    cell(outcome.six_sigma.as_ref().map(|result| result.dim.tol.t())),
+                    cell(outcome.reject_ppm()),
+                ]
+            })
+            .collect();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].len())
+                    .chain(std::iter::once(header[i].len()))
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{cell:>width$}"))
+                .collect();
+            writeln!(f, "{}", cells.join("  "))?;
+        }
+        Ok(())
+    }
+}
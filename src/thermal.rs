@@ -0,0 +1,126 @@
+use crate::calc;
+use crate::dim::{Basic, Contributor, Reviewed, Stack};
+use crate::error::{Error, Result};
+use crate::tol::Bilateral;
+use crate::utils::to_f64;
+
+use num_traits::Float;
+
+/// Operating temperature range of an assembly whose dims are specified at a
+/// reference temperature.
+///
+/// Each dim with a `Material` grows by `1 + cte * (t - reference)`, which
+/// shifts its nominal and scales its tolerance. The gap is linear in
+/// temperature, so an analysis over the range is the envelope of the
+/// analysis at the two ends of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thermal<T = f64> {
+    pub reference: T,
+    pub min: T,
+    pub max: T,
+}
+
+impl<T: Float> Thermal<T> {
+    pub fn new(reference: T, min: T, max: T) -> Result<Thermal<T>> {
+        if min > max {
+            return Err(Error::InvertedLimits {
+                lower: to_f64(min),
+                upper: to_f64(max),
+            });
+        }
+        Ok(Thermal {
+            reference,
+            min,
+            max,
+        })
+    }
+
+    /// The stack at `temperature`. Dims without a material do not change,
+    /// and the correlations between dims are kept.
+    pub fn at(&self, stack: &Stack<T>, temperature: T) -> Stack<T> {
+        let delta = temperature - self.reference;
+        let mut at = stack.clone();
+        for dim in &mut at.dims {
+            if let Some(material) = dim.basic().material.as_ref() {
+                *dim = grow(dim, material.growth(delta));
+            }
+        }
+        at
+    }
+
+    /// Window covering the result of `analysis` at every temperature in the
+    /// range, around the nominal at the reference temperature.
+    pub fn envelope(
+        &self,
+        stack: &Stack<T>,
+        analysis: impl Fn(&Stack<T>) -> Result<Basic<T>>,
+    ) -> Result<Basic<T>> {
+        Ok(self.window(
+            analysis(&self.at(stack, self.reference))?,
+            analysis(&self.at(stack, self.min))?,
+            analysis(&self.at(stack, self.max))?,
+        ))
+    }
+
+    /// `envelope` of an analysis that cannot fail.
+    fn envelope_of(&self, stack: &Stack<T>, analysis: impl Fn(&Stack<T>) -> Basic<T>) -> Basic<T> {
+        self.window(
+            analysis(&self.at(stack, self.reference)),
+            analysis(&self.at(stack, self.min)),
+            analysis(&self.at(stack, self.max)),
+        )
+    }
+
+    /// Window covering the results at both ends of the range, around the
+    /// nominal of the result at the reference temperature.
+    fn window(&self, reference: Basic<T>, cold: Basic<T>, hot: Basic<T>) -> Basic<T> {
+        let mut basic = Basic::from_limits(
+            &reference.name,
+            reference.nom.x,
+            cold.absolute_lower().min(hot.absolute_lower()),
+            cold.absolute_upper().max(hot.absolute_upper()),
+        );
+        basic.desc = format!(
+            "(from {} to {}, reference {})",
+            to_f64(self.min),
+            to_f64(self.max),
+            to_f64(self.reference)
+        );
+        basic
+    }
+
+    /// `calc::wc` over the temperature range.
+    pub fn wc(&self, stack: &Stack<T>) -> Basic<T> {
+        self.envelope_of(stack, calc::wc)
+    }
+
+    /// `calc::rss` over the temperature range.
+    pub fn rss(&self, stack: &Stack<T>) -> Result<Basic<T>> {
+        self.envelope(stack, calc::rss)
+    }
+
+    /// `calc::mrss` over the temperature range.
+    pub fn mrss(&self, stack: &Stack<T>) -> Result<Basic<T>> {
+        self.envelope(stack, calc::mrss)
+    }
+
+    /// `calc::six_sigma` over the temperature range.
+    pub fn six_sigma(&self, stack: &Stack<T>, at: T) -> Result<Basic<T>> {
+        self.envelope(stack, |stack| calc::six_sigma(stack, at).map(|r| r.dim))
+    }
+}
+
+/// A dim scaled by `factor`, along with its process data.
+fn grow<T: Float>(dim: &Contributor<T>, factor: T) -> Contributor<T> {
+    let mut grown = dim.basic().clone();
+    grown.nom.x = grown.nom.x * factor;
+    grown.tol = Bilateral::unequal(grown.tol.upper * factor, grown.tol.lower * factor);
+    match dim {
+        Contributor::Basic(_) => Contributor::Basic(grown),
+        Contributor::Reviewed(reviewed) => Contributor::Reviewed(Reviewed {
+            dim: grown,
+            target_process_sigma: reviewed.target_process_sigma,
+            distribution: reviewed.distribution.as_ref().map(|d| d.scaled(factor)),
+        }),
+    }
+}
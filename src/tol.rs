@@ -1,28 +1,30 @@
+use num_traits::Float;
+
 #[derive(Clone)]
-pub struct Bilateral {
-    pub upper: f32,
-    pub lower: f32,
+pub struct Bilateral<T = f64> {
+    pub upper: T,
+    pub lower: T,
 }
 
-impl Bilateral {
-    pub fn new(upper: f32, lower: f32) -> Bilateral {
+impl<T: Float> Bilateral<T> {
+    pub fn new(upper: T, lower: T) -> Bilateral<T> {
         Bilateral::asymmetric(upper, lower)
     }
     /// Create a bilateral tolerance with different upper and lower bounds
-    pub fn asymmetric(upper: f32, lower: f32) -> Bilateral {
+    pub fn asymmetric(upper: T, lower: T) -> Bilateral<T> {
         // tolerances are always relative to the nominal value
         if upper < lower {
-            return Bilateral {
+            Bilateral {
                 upper: lower,
                 lower: upper,
-            };
+            }
         } else {
-            return Bilateral { upper, lower };
+            Bilateral { upper, lower }
         }
     }
 
     /// Create a bilateral tolerance where the upper and lower bounds are the same distance from the nominal value
-    pub fn symmetric(tol: f32) -> Bilateral {
+    pub fn symmetric(tol: T) -> Bilateral<T> {
         Bilateral {
             upper: tol,
             lower: -tol,
@@ -31,12 +33,12 @@ impl Bilateral {
 
     /// Create a bilateral tolerance with different upper and lower bounds
     /// alias for `asymmetric`
-    pub fn unequal(upper: f32, lower: f32) -> Bilateral {
+    pub fn unequal(upper: T, lower: T) -> Bilateral<T> {
         Bilateral::asymmetric(upper, lower)
     }
 
-    pub fn t(&self) -> f32 {
-        return self.upper - self.lower;
+    pub fn t(&self) -> T {
+        self.upper - self.lower
     }
 }
 
@@ -62,5 +64,9 @@ mod tests {
         assert_eq!(tol.upper, 2.0);
         assert_eq!(tol.lower, -1.0);
         assert_eq!(tol.t(), 3.0);
+
+        // single precision still works
+        let tol = Bilateral::symmetric(1.0f32);
+        assert_eq!(tol.t(), 2.0f32);
     }
 }
@@ -1,5 +1,6 @@
 use num_traits::cast::FromPrimitive;
 use num_traits::float::Float;
+use std::cmp::Ordering;
 
 // https://stackoverflow.com/questions/28655362/how-does-one-round-a-floating-point-number-to-a-specified-number-of-digits
 fn round<T: Float + FromPrimitive>(x: T, decimals: Option<u32>) -> T {
@@ -7,6 +8,21 @@ fn round<T: Float + FromPrimitive>(x: T, decimals: Option<u32>) -> T {
     (x * y).round() / y
 }
 
+/// Convert an `f64` constant into the working float type.
+pub(crate) fn cast<T: Float>(x: f64) -> T {
+    T::from(x).unwrap()
+}
+
+/// Convert a working float into `f64`, e.g. for error reporting.
+pub(crate) fn to_f64<T: Float>(x: T) -> f64 {
+    x.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Total order of working floats, which unlike `partial_cmp` holds for NaN.
+pub(crate) fn total_cmp<T: Float>(a: &T, b: &T) -> Ordering {
+    to_f64(*a).total_cmp(&to_f64(*b))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,194 @@
+use crate::dim::{Basic, Reviewed};
+use crate::utils::{cast, to_f64};
+
+use num_traits::Float;
+use std::fmt;
+
+/// A problem found by `Stack::validate`.
+///
+/// Warnings do not stop an analysis from running, they flag stack-ups that
+/// should be looked at before the results are trusted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// More than one dim shares the same name.
+    DuplicateName(String),
+    /// The dim has a sensitivity of zero and does not contribute.
+    ZeroSensitivity(String),
+    /// A tolerance bound is larger than the nominal itself.
+    ToleranceExceedsNominal(String),
+    /// The process mean lies outside the dim's tolerance limits.
+    MeanOutsideLimits(String),
+    /// Dims were reviewed with different target process sigmas, while
+    /// `calc::rss` assumes equal sigma.
+    MixedProcessSigma(Vec<f64>),
+    /// A correlation names a dim that is not in the stack, e.g. one removed
+    /// after it was correlated, so the correlation is ignored.
+    UnknownCorrelatedDim(String),
+    /// Every dim points in the same direction, so the loop cannot close.
+    OpenLoop,
+    /// The closed gap is larger than any single dim, which usually means a
+    /// missing dim or a flipped direction.
+    SuspiciousClosure(f64),
+    /// A few non-normal reviewed dims dominate, so the sum of the reviewed
+    /// dims is far from the normal assumed by `calc::six_sigma`. Reported
+    /// with the skewness and excess kurtosis of the sum.
+    NonNormalAssembly { skewness: f64, excess_kurtosis: f64 },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::DuplicateName(name) => write!(f, "'{name}' is used by more than one dim"),
+            Warning::ZeroSensitivity(name) => write!(f, "'{name}' has zero sensitivity"),
+            Warning::ToleranceExceedsNominal(name) => {
+                write!(f, "'{name}' has a tolerance larger than its nominal")
+            }
+            Warning::MeanOutsideLimits(name) => {
+                write!(f, "'{name}' has a process mean outside its limits")
+            }
+            Warning::MixedProcessSigma(sigmas) => write!(
+                f,
+                "dims use different process sigmas {sigmas:?}, RSS assumes equal sigma"
+            ),
+            Warning::UnknownCorrelatedDim(name) => {
+                write!(f, "'{name}' is correlated but not in the stack")
+            }
+            Warning::OpenLoop => write!(f, "all dims point in the same direction"),
+            Warning::SuspiciousClosure(gap) => {
+                write!(f, "closed gap {gap} is larger than any dim in the stack")
+            }
+            Warning::NonNormalAssembly {
+                skewness,
+                excess_kurtosis,
+            } => write!(
+                f,
+                "assembly has skewness {skewness} and excess kurtosis {excess_kurtosis}, \
+                 the normal approximation is poor in the tails"
+            ),
+        }
+    }
+}
+
+/// Checks that only need the design dims.
+pub(crate) fn check_dims<T: Float>(dims: &[Basic<T>]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    let mut seen: Vec<&str> = Vec::new();
+    for dim in dims {
+        if seen.contains(&dim.name.as_str()) {
+            let warning = Warning::DuplicateName(dim.name.clone());
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        } else {
+            seen.push(&dim.name);
+        }
+    }
+
+    for dim in dims {
+        if dim.a == T::zero() {
+            warnings.push(Warning::ZeroSensitivity(dim.name.clone()));
+        }
+        // a zero nominal is a pure variation contributor
+        let largest_tol = dim.tol.upper.abs().max(dim.tol.lower.abs());
+        if dim.nom.x != T::zero() && largest_tol > dim.nom.x.abs() {
+            warnings.push(Warning::ToleranceExceedsNominal(dim.name.clone()));
+        }
+    }
+
+    let contributing: Vec<T> = dims
+        .iter()
+        .map(|dim| dim.absolute_nominal_adj())
+        .filter(|x| *x != T::zero())
+        .collect();
+    if contributing.len() > 1 {
+        let positive = contributing.iter().any(|x| *x > T::zero());
+        let negative = contributing.iter().any(|x| *x < T::zero());
+        if !(positive && negative) {
+            warnings.push(Warning::OpenLoop);
+        } else {
+            let gap = contributing.iter().fold(T::zero(), |acc, &x| acc + x);
+            let largest = contributing
+                .iter()
+                .fold(T::zero(), |acc, x| acc.max(x.abs()));
+            if gap.abs() >= largest {
+                warnings.push(Warning::SuspiciousClosure(to_f64(gap)));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Checks that the correlations name dims of the stack.
+pub(crate) fn check_correlations<T: Float>(
+    names: &[&str],
+    correlations: &[(String, String, T)],
+) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for name in correlations.iter().flat_map(|(a, b, _)| [a, b]) {
+        let warning = Warning::UnknownCorrelatedDim(name.clone());
+        if !names.contains(&name.as_str()) && !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    warnings
+}
+
+/// Checks on the process data of reviewed dims.
+pub(crate) fn check_reviewed<T: Float>(dims: &[&Reviewed<T>]) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for rdim in dims {
+        if let Some(distribution) = &rdim.distribution {
+            let mean = distribution.mean();
+            if mean < rdim.dim.absolute_lower() || mean > rdim.dim.absolute_upper() {
+                warnings.push(Warning::MeanOutsideLimits(rdim.dim.name.clone()));
+            }
+        }
+    }
+    let mut sigmas: Vec<T> = Vec::new();
+    for rdim in dims {
+        if !sigmas.contains(&rdim.target_process_sigma) {
+            sigmas.push(rdim.target_process_sigma);
+        }
+    }
+    if sigmas.len() > 1 {
+        warnings.push(Warning::MixedProcessSigma(
+            sigmas.into_iter().map(to_f64).collect(),
+        ));
+    }
+    warnings.extend(check_normality(dims));
+    warnings
+}
+
+/// Shape of the sum of the reviewed dims, from the cumulants of each. The
+/// sum of many similar dims is close to normal whatever their distribution;
+/// the limits flag about four or fewer equal uniform dims, or a skewed dim
+/// dominating.
+pub(crate) fn check_normality<T: Float>(dims: &[&Reviewed<T>]) -> Option<Warning> {
+    const SKEWNESS: f64 = 0.1;
+    const EXCESS_KURTOSIS: f64 = 0.25;
+    let (mut variance, mut third, mut fourth) = (T::zero(), T::zero(), T::zero());
+    for rdim in dims {
+        if let Some(distribution) = &rdim.distribution {
+            let c = rdim.dim.nom.x.signum() * rdim.dim.a;
+            let v = distribution.variance() * c * c;
+            variance = variance + v;
+            third = third + distribution.skewness() * v.powf(cast(1.5)) * c.signum();
+            fourth = fourth + (distribution.kurtosis() - cast(3.0)) * v * v;
+        }
+    }
+    if variance <= T::zero() {
+        return None;
+    }
+    let skewness = to_f64(third / variance.powf(cast(1.5)));
+    let excess_kurtosis = to_f64(fourth / (variance * variance));
+    if skewness.abs() > SKEWNESS || excess_kurtosis.abs() > EXCESS_KURTOSIS {
+        Some(Warning::NonNormalAssembly {
+            skewness,
+            excess_kurtosis,
+        })
+    } else {
+        None
+    }
+}
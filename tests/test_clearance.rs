@@ -0,0 +1,99 @@
+use dimstack_rs::calc;
+use dimstack_rs::clearance::{Clearance, Shift};
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+
+fn hole() -> dim::Basic {
+    dim::Basic::builder("hole")
+        .nominal(10.0)
+        .unequal(0.1, 0.0)
+        .build()
+}
+
+fn pin() -> dim::Basic {
+    dim::Basic::builder("pin")
+        .nominal(9.9)
+        .unequal(0.0, -0.1)
+        .build()
+}
+
+#[test]
+fn test_clearance_limits() {
+    let float = Clearance::new("float", hole(), pin(), Shift::Uniform).unwrap();
+    assert!((float.nominal() - 0.1).abs() < 1e-12);
+    assert!((float.min() - 0.1).abs() < 1e-12);
+    assert!((float.max() - 0.3).abs() < 1e-12);
+    let basic = float.basic();
+    assert_eq!(basic.nom.x, 0.0);
+    assert!((basic.absolute_lower() + 0.15).abs() < 1e-12);
+    assert!((basic.absolute_upper() - 0.15).abs() < 1e-12);
+
+    let pushed = Clearance::new(
+        "float",
+        hole(),
+        pin(),
+        Shift::Extreme(dim::Direction::Negative),
+    )
+    .unwrap()
+    .basic();
+    assert!((pushed.nom.x + 0.05).abs() < 1e-12);
+    assert!((pushed.absolute_lower() + 0.15).abs() < 1e-12);
+    assert!((pushed.absolute_upper() + 0.05).abs() < 1e-12);
+
+    let tight = dim::Basic::builder("pin").nominal(10.2).sym(0.05).build();
+    assert!(matches!(
+        Clearance::new("float", hole(), tight, Shift::Uniform),
+        Err(Error::NoClearance)
+    ));
+
+    // the limits allow a fit, but the measured parts never clear
+    let measured = |basic: dim::Basic, size: f64| -> dim::Reviewed {
+        dim::Reviewed::new(
+            basic,
+            3.0,
+            Some(dist::Distribution::Empirical(
+                dist::Empirical::new(vec![size]).unwrap(),
+            )),
+        )
+        .unwrap()
+    };
+    let float = Clearance::new(
+        "float",
+        measured(hole(), 10.0),
+        measured(pin(), 10.05),
+        Shift::Uniform,
+    )
+    .unwrap();
+    assert_eq!(float.reviewed().err(), Some(Error::NoClearance));
+}
+
+#[test]
+fn test_clearance_in_stack() {
+    let float = Clearance::new("float", hole(), pin(), Shift::Uniform).unwrap();
+    let stack = dim::Stack::builder("bracket")
+        .push(dim::Basic::builder("base").nominal(25.0).sym(0.05))
+        .push(float.reviewed().unwrap())
+        .build();
+    // the float does not move the nominal, and adds its extremes to WC
+    assert_eq!(calc::closed(&stack).nom.x, 25.0);
+    assert!((calc::wc(&stack).tol.t() - 0.4).abs() < 1e-12);
+
+    // uniform within a random clearance c has variance E[c^2] / 12
+    let float = float.reviewed().unwrap().distribution.unwrap();
+    let c_std: f64 = 2f64.sqrt() * 0.1 / 6.0;
+    let expected = (0.2 * 0.2 + c_std * c_std) / 12.0;
+    assert!(float.mean().abs() < 1e-9);
+    assert!((float.variance() / expected - 1.0).abs() < 0.01);
+
+    let pushed = Clearance::new(
+        "float",
+        hole(),
+        pin(),
+        Shift::Extreme(dim::Direction::Positive),
+    )
+    .unwrap();
+    let pushed = pushed.reviewed().unwrap().distribution.unwrap();
+    assert!((pushed.mean() - 0.1).abs() < 1e-4);
+    assert!((pushed.std_dev() - c_std / 2.0).abs() < 1e-4);
+}
@@ -0,0 +1,130 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+
+fn uniform(name: &str, nom: f64, tol: f64) -> dim::Reviewed {
+    let (lower, upper) = (nom - tol, nom + tol);
+    dim::Basic::builder(name)
+        .nominal(nom)
+        .sym(tol)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Uniform(
+            dist::Uniform::new(lower, upper).unwrap(),
+        ))
+        .build()
+        .unwrap()
+}
+
+fn requirement(result: &dim::Reviewed, ll: f64, ul: f64) -> dim::Requirement {
+    dim::Requirement::new(
+        "gap".to_owned(),
+        "".to_owned(),
+        result.distribution.clone().unwrap(),
+        ll,
+        ul,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_convolve_uniforms() {
+    // two uniforms +/- 0.1 sum to a triangle +/- 0.2
+    let stack: dim::Stack = dim::Stack::builder("uniforms")
+        .push(uniform("a", 10.0, 0.1))
+        .push(uniform("b", -5.0, 0.1))
+        .build();
+    let result = calc::convolve(&stack, 3.0).unwrap();
+    let d = result.distribution.as_ref().unwrap();
+    assert!((d.mean() - 5.0).abs() < 1e-9);
+    assert!((d.variance() - 0.02 / 3.0).abs() < 1e-7);
+    let spec = requirement(&result, 4.85, 5.15);
+    let exact = 0.05f64.powi(2) / 0.04;
+    assert!((spec.yield_loss_probability() / exact - 1.0).abs() < 1e-3);
+    // the triangle ends at +/- 0.2, the normal approximation does not
+    assert!(requirement(&result, 4.79, 5.21).r() < 1e-6);
+    let normal = calc::six_sigma(&stack, 3.0).unwrap();
+    assert!(requirement(&normal, 4.79, 5.21).r() > 1.0);
+}
+
+#[test]
+fn test_convolve_normals() {
+    let stack: dim::Stack = dim::Stack::builder("normals")
+        .push(
+            dim::Basic::builder("a")
+                .nominal(10.0)
+                .sym(0.3)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(dim::Basic::builder("b").nominal(-5.0).sym(0.4))
+        .push(dim::Basic::builder("c").nominal(1.0).sym(0.0))
+        .build();
+    let result = calc::convolve(&stack, 3.0).unwrap();
+    let d = result.distribution.as_ref().unwrap();
+    assert!((d.mean() - 6.0).abs() < 1e-9);
+    let std_dev = (0.01f64 + 0.16 / 9.0).sqrt();
+    assert!((d.std_dev() / std_dev - 1.0).abs() < 1e-4);
+    assert!((result.dim.tol.t() / 2.0 / (3.0 * std_dev) - 1.0).abs() < 1e-3);
+    // reject PPM at +/- 4.5 sigma, 6.795 for a normal
+    let spec = requirement(&result, 6.0 - 4.5 * std_dev, 6.0 + 4.5 * std_dev);
+    assert!((spec.r() / 6.795 - 1.0).abs() < 1e-2);
+    // deterministic
+    let again = calc::convolve(&stack, 3.0).unwrap();
+    assert_eq!(
+        requirement(&again, 5.7, 6.2).r(),
+        requirement(&result, 5.7, 6.2).r()
+    );
+}
+
+#[test]
+fn test_convolve_errors() {
+    let mut stack: dim::Stack = dim::Stack::builder("pair")
+        .push(uniform("a", 10.0, 0.1))
+        .push(uniform("b", -5.0, 0.1))
+        .build();
+    stack.correlate("a", "b", 0.5).unwrap();
+    assert_eq!(
+        calc::convolve(&stack, 3.0).err(),
+        Some(Error::CorrelatedDims("Convolution"))
+    );
+    let empty: dim::Stack = dim::Stack::builder("empty").build();
+    assert_eq!(
+        calc::convolve(&empty, 3.0).err(),
+        Some(Error::TooFewDims {
+            analysis: "Convolution",
+            required: 1,
+            found: 0
+        })
+    );
+    let fixed: dim::Stack = dim::Stack::builder("fixed")
+        .push(dim::Basic::builder("a").nominal(10.0).sym(0.0).build())
+        .build();
+    assert_eq!(calc::convolve(&fixed, 3.0).err(), Some(Error::NoVariation));
+}
+
+#[test]
+fn test_convolve_point_mass() {
+    // a single measurement has no spread, so it only shifts the assembly
+    let measured: dim::Reviewed = dim::Basic::builder("measured")
+        .nominal(10.0)
+        .sym(0.1)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Empirical(
+            dist::Empirical::new(vec![10.02]).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    let alone: dim::Stack = dim::Stack::builder("alone").push(measured.clone()).build();
+    assert_eq!(calc::convolve(&alone, 3.0).err(), Some(Error::NoVariation));
+
+    let stack = dim::Stack::builder("pair")
+        .push(measured)
+        .push(uniform("b", -5.0, 0.1))
+        .build();
+    let result = calc::convolve(&stack, 3.0).unwrap();
+    let dist = result.distribution.unwrap();
+    assert!((dist.mean() - 5.02).abs() < 1e-3);
+    assert!((dist.std_dev() - 0.1 / 3.0_f64.sqrt()).abs() < 1e-3);
+}
@@ -0,0 +1,139 @@
+use dimstack_rs::calc;
+use dimstack_rs::correlation::Correlation;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::validate::Warning;
+
+fn pair(second: f64) -> dim::Stack {
+    dim::Stack::builder("pair")
+        .push(dim::Basic::builder("c").nominal(10.0).sym(0.1))
+        .push(dim::Basic::builder("g").nominal(second).sym(0.1))
+        .build()
+}
+
+#[test]
+fn test_correlation_rss() {
+    let mut stack = pair(5.0);
+    let independent = calc::rss(&stack).unwrap().tol.t() / 2.0;
+    assert!((independent - 0.02f64.sqrt()).abs() < 1e-12);
+
+    stack.correlate("c", "g", 1.0).unwrap();
+    assert!((calc::rss(&stack).unwrap().tol.t() / 2.0 - 0.2).abs() < 1e-12);
+    stack.correlate("c", "g", 0.5).unwrap();
+    assert!((calc::rss(&stack).unwrap().tol.t() / 2.0 - 0.03f64.sqrt()).abs() < 1e-12);
+
+    // sizes that grow together cancel when measured in opposite directions
+    let mut stack = pair(-5.0);
+    stack.correlate("c", "g", 1.0).unwrap();
+    assert!((calc::rss(&stack).unwrap().tol.t()).abs() < 1e-9);
+    assert!(calc::mrss(&stack).unwrap().tol.t().abs() < 1e-9);
+}
+
+#[test]
+fn test_correlation_six_sigma_and_monte_carlo() {
+    let mut stack: dim::Stack = dim::Stack::builder("bearings")
+        .push(
+            dim::Basic::builder("c")
+                .nominal(10.0)
+                .sym(0.1)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(
+            dim::Basic::builder("g")
+                .nominal(5.0)
+                .sym(0.1)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(
+            dim::Basic::builder("h")
+                .nominal(-14.0)
+                .sym(0.1)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build();
+    stack.correlate("c", "g", 0.8).unwrap();
+    let s: f64 = 0.1 / 3.0;
+    let expected = (3.0 * s * s + 2.0 * 0.8 * s * s).sqrt();
+    let six_sigma = calc::six_sigma(&stack, 3.0).unwrap();
+    assert!((six_sigma.distribution.unwrap().std_dev() - expected).abs() < 1e-12);
+
+    let mc = calc::monte_carlo(&stack, 3.0, 100_000, 7).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.std_dev() / expected - 1.0).abs() < 0.01);
+    assert!((mc_dist.mean() - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_correlation_invalid() {
+    let mut stack = pair(5.0);
+    assert_eq!(
+        stack.correlate("c", "x", 0.5),
+        Err(Error::UnknownDim("x".to_owned()))
+    );
+    assert_eq!(
+        stack.correlate("c", "g", 1.5),
+        Err(Error::InvalidCorrelation {
+            row: 0,
+            col: 1,
+            value: 1.5
+        })
+    );
+    let matrix = vec![
+        vec![1.0, 0.9, -0.9],
+        vec![0.9, 1.0, 0.9],
+        vec![-0.9, 0.9, 1.0],
+    ];
+    assert_eq!(
+        Correlation::new(matrix).err(),
+        Some(Error::NotPositiveDefinite)
+    );
+
+    let correlation: Correlation = Correlation::identity(2);
+    assert_eq!(correlation.get(0, 5), 0.0);
+    assert_eq!(correlation.get(5, 5), 1.0);
+}
+
+#[test]
+fn test_correlation_edited_invalid() {
+    let mut stack = pair(5.0);
+    stack.correlate("c", "g", 0.5).unwrap();
+    stack.correlations[0].2 = 1.5;
+    assert!(matches!(
+        stack.correlation(),
+        Err(Error::InvalidCorrelation { .. })
+    ));
+    assert!(calc::rss(&stack).is_err());
+    assert!(calc::contributions(&stack).is_err());
+}
+
+#[test]
+fn test_correlation_follows_dims() {
+    let mut stack = pair(5.0);
+    stack.correlate("c", "g", 0.5).unwrap();
+    stack
+        .dims
+        .insert(0, dim::Basic::builder("f").nominal(-14.0).sym(0.1).into());
+    let correlation = stack.correlation().unwrap().unwrap();
+    assert_eq!(correlation.get(0, 1), 0.0);
+    assert_eq!(correlation.get(1, 2), 0.5);
+
+    stack.dims.swap(0, 2);
+    let correlation = stack.correlation().unwrap().unwrap();
+    assert_eq!(correlation.get(0, 1), 0.5);
+    assert_eq!(correlation.get(1, 2), 0.0);
+
+    // a removed dim leaves the rest independent, and validate says so
+    stack.dims.retain(|dim| dim.name() != "c");
+    assert!(stack.correlation().unwrap().is_none());
+    assert!(
+        stack
+            .validate()
+            .contains(&Warning::UnknownCorrelatedDim("c".to_owned()))
+    );
+}
@@ -7,13 +7,11 @@ fn test_positive_unequal_bilateral() {
     let t = tol::Bilateral::unequal(0.005, -0.004);
     assert_eq!(t.upper, 0.005);
     assert_eq!(t.lower, -0.004);
-    let d = dim::Basic {
-        nom: dim::Vec1D::new(1.0),
-        tol: t,
-        a: 0.0,
-        name: "a".to_string(),
-        desc: "".to_string(),
-    };
+    let d = dim::Basic::builder("a")
+        .nominal(1.0)
+        .tol(t)
+        .sensitivity(0.0)
+        .build();
     assert_eq!(d.absolute_upper(), 1.005);
     assert_eq!(d.absolute_lower(), 0.996);
 }
@@ -23,13 +21,11 @@ fn test_negative_unequal_bilateral() {
     let t = tol::Bilateral::unequal(0.005, -0.004);
     assert_eq!(t.upper, 0.005);
     assert_eq!(t.lower, -0.004);
-    let d = dim::Basic {
-        nom: dim::Vec1D::new(-1.0),
-        tol: t,
-        a: 0.0,
-        name: "a".to_string(),
-        desc: "".to_string(),
-    };
+    let d: dim::Basic = dim::Basic::builder("a")
+        .nominal(-1.0)
+        .tol(t)
+        .sensitivity(0.0)
+        .build();
     assert_eq!(d.nom.x.abs(), 1.0);
     assert_eq!(d.nom.x, -1.0);
 
@@ -39,3 +35,60 @@ fn test_negative_unequal_bilateral() {
     assert_eq!(d.absolute_upper(), -0.996);
     assert_eq!(d.absolute_lower(), -1.005);
 }
+
+#[test]
+fn test_large_nominal_precision() {
+    // 208 mm shaft with micron tolerances, as in the MITCalc stack
+    let d: dim::Basic<f64> = dim::Basic::builder("a")
+        .desc("Shaft")
+        .nominal(208.0)
+        .unequal(0.001, -0.0005)
+        .build();
+    assert!((d.absolute_upper() - 208.001).abs() < 1e-12);
+    assert!((d.absolute_lower() - 207.9995).abs() < 1e-12);
+    assert!((d.tol.t() - 0.0015).abs() < 1e-15);
+}
+
+#[test]
+fn test_builder_matches_literal() {
+    let built = dim::Basic::builder("a")
+        .desc("Shaft")
+        .nominal(208.0)
+        .sym(0.036)
+        .reviewed(6.0)
+        .skew(0.25)
+        .build()
+        .unwrap();
+    let literal = dim::Basic {
+        nom: dim::Vec1D::new(208.0),
+        tol: tol::Bilateral::symmetric(0.036),
+        a: 1.0,
+        name: "a".to_owned(),
+        desc: "Shaft".to_owned(),
+        material: None,
+        part: None,
+        feature: None,
+    };
+    let literal = dim::Reviewed::of_basic(literal, 6.0)
+        .unwrap()
+        .assume_normal_dist_skewed(0.25)
+        .unwrap();
+    assert_eq!(built.dim.name, literal.dim.name);
+    assert_eq!(built.dim.desc, literal.dim.desc);
+    assert_eq!(built.dim.a, literal.dim.a);
+    assert_eq!(built.dim.absolute_upper(), literal.dim.absolute_upper());
+    let built_mean: f64 = built.distribution.unwrap().mean();
+    assert_eq!(built_mean, literal.distribution.unwrap().mean());
+    assert!((built_mean - 208.009).abs() < 1e-12);
+
+    let stack = dim::Stack::builder("stack")
+        .desc("built")
+        .push(dim::Basic::builder("b").nominal(-1.75).unequal(0.0, -0.06))
+        .push(built.dim)
+        .build();
+    assert_eq!(stack.dims.len(), 2);
+    assert_eq!(stack.dims[0].basic().absolute_lower(), -1.75);
+    assert!(stack.dims[0].reviewed().is_none());
+    assert!(stack.dims[1].reviewed().is_none());
+    assert_eq!(stack.description, "built");
+}
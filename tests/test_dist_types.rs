@@ -1,18 +1,16 @@
-use dimstack_rs::{dim, dist, tol};
+use dimstack_rs::{calc, dim, dist, tol};
 
 #[test]
 fn test_positive_dist() {
-    let measurements = vec![1.1, 0.9];
-    let measurements_dist = dist::Normal::fit(&measurements);
+    let measurements: Vec<f64> = vec![1.1, 0.9];
+    let measurements_dist = dist::Normal::fit(&measurements).unwrap();
     let tol = tol::Bilateral::symmetric(0.3);
     let reviewed_dim = dim::Reviewed {
-        dim: dim::Basic {
-            nom: dim::Vec1D::new(1.0),
-            tol,
-            a: 0.0,
-            name: "1".to_owned(),
-            desc: "".to_owned(),
-        },
+        dim: dim::Basic::builder("1")
+            .nominal(1.0)
+            .tol(tol)
+            .sensitivity(0.0)
+            .build(),
         target_process_sigma: 0.0,
         distribution: Some(dist::Distribution::Normal(measurements_dist)),
     };
@@ -31,16 +29,10 @@ fn test_positive_dist() {
 
 #[test]
 fn test_negative_dist() {
-    let measurements = vec![-1.1, -0.9];
-    let measurements_dist = dist::Normal::fit(&measurements);
+    let measurements: Vec<f64> = vec![-1.1, -0.9];
+    let measurements_dist = dist::Normal::fit(&measurements).unwrap();
     let reviewed_dim = dim::Reviewed {
-        dim: dim::Basic {
-            nom: dim::Vec1D::new(-1.0),
-            tol: tol::Bilateral::symmetric(0.3),
-            a: 1.0,
-            name: "1".to_owned(),
-            desc: "".to_owned(),
-        },
+        dim: dim::Basic::builder("1").nominal(-1.0).sym(0.3).build(),
         target_process_sigma: 0.0,
         distribution: Some(dist::Distribution::Normal(measurements_dist)),
     };
@@ -56,3 +48,150 @@ fn test_negative_dist() {
 
     assert!((reviewed_dim.yield_probability() - 0.9973).abs() < 0.0001);
 }
+
+#[test]
+fn test_requirement_ppm() {
+    // +/- 6 sigma limits reject ~0.002 PPM, which `1.0 - yield` in f32 rounds to 0
+    let spec = dim::Requirement::new(
+        "spec".to_owned(),
+        "".to_owned(),
+        dist::Distribution::Normal(dist::Normal::new(0.0f64, 1.0).unwrap()),
+        -6.0,
+        6.0,
+    )
+    .unwrap();
+    assert!((spec.r() - 0.001973175).abs() < 1e-8);
+
+    let spec = dim::Requirement::new(
+        "spec".to_owned(),
+        "".to_owned(),
+        dist::Distribution::Normal(dist::Normal::new(0.0f32, 1.0).unwrap()),
+        -6.0,
+        6.0,
+    )
+    .unwrap();
+    assert!(spec.r() > 0.0);
+}
+
+#[test]
+fn test_six_sigma_uniform() {
+    // a uniform +/- 0.1 has a standard deviation of 0.1 / sqrt(3)
+    let uniform = dim::Basic::builder("a")
+        .nominal(10.0)
+        .sym(0.1)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Uniform(
+            dist::Uniform::new(9.9, 10.1).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    assert!((uniform.std_dev_eff() - 0.1 / 3f64.sqrt()).abs() < 1e-12);
+    assert!((uniform.k() - 1.0).abs() < 1e-12);
+
+    let normal = dim::Basic::builder("b")
+        .nominal(-5.0)
+        .sym(0.03)
+        .reviewed(3.0)
+        .build()
+        .unwrap();
+    let stack: dim::Stack = dim::Stack::builder("mixed")
+        .push(uniform)
+        .push(normal)
+        .build();
+    let result = calc::six_sigma(&stack, 3.0).unwrap();
+    let std_dev = (0.01f64 / 3.0 + 0.0001).sqrt();
+    assert!((result.dim.tol.t() / 2.0 - 3.0 * std_dev).abs() < 1e-12);
+    assert!((result.distribution.unwrap().std_dev() - std_dev).abs() < 1e-12);
+}
+
+#[test]
+fn test_six_sigma_sensitivity() {
+    // a sensitivity of 2 doubles the dim's standard deviation in the stack
+    let stack: dim::Stack = dim::Stack::builder("lever")
+        .push(
+            dim::Basic::builder("arm")
+                .nominal(10.0)
+                .sym(0.3)
+                .sensitivity(2.0)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(
+            dim::Basic::builder("pin")
+                .nominal(-5.0)
+                .sym(0.3)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build();
+    let six_sigma = calc::six_sigma(&stack, 3.0).unwrap();
+    let std_dev = six_sigma.distribution.unwrap().std_dev();
+    assert!((std_dev - 0.05f64.sqrt()).abs() < 1e-12);
+    let exact = calc::convolve(&stack, 3.0).unwrap();
+    assert!((exact.distribution.unwrap().std_dev() / std_dev - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_reviewed_yield_uniform() {
+    let inside: dim::Reviewed = dim::Basic::builder("a")
+        .nominal(10.0)
+        .sym(0.1)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Uniform(
+            dist::Uniform::new(9.95, 10.05).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    assert_eq!(inside.yield_loss_probability(), 0.0);
+    assert_eq!(inside.yield_probability(), 1.0);
+
+    // half of the process falls above the upper limit
+    let shifted: dim::Reviewed = dim::Basic::builder("b")
+        .nominal(-10.0)
+        .sym(0.1)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Uniform(
+            dist::Uniform::new(-10.0, -9.8).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    assert!((shifted.yield_loss_probability() - 0.5).abs() < 1e-12);
+    assert!((shifted.yield_probability() - 0.5).abs() < 1e-12);
+}
+
+#[test]
+fn test_six_sigma_non_normal_warning() {
+    let uniform = |name: &str, nom: f64| -> dim::Reviewed {
+        dim::Basic::builder(name)
+            .nominal(nom)
+            .sym(0.1)
+            .reviewed(3.0)
+            .distribution(dist::Distribution::Uniform(
+                dist::Uniform::new(nom - 0.1, nom + 0.1).unwrap(),
+            ))
+            .build()
+            .unwrap()
+    };
+    let stack: dim::Stack = dim::Stack::builder("uniforms")
+        .push(uniform("a", 10.0))
+        .push(uniform("b", -5.0))
+        .build();
+    let result = calc::six_sigma(&stack, 3.0).unwrap();
+    assert!(result.dim.desc.contains("warning"));
+    assert!(result.dim.desc.contains("excess kurtosis -0.6"));
+
+    let normal: dim::Stack = dim::Stack::builder("normals")
+        .push(
+            dim::Basic::builder("a")
+                .nominal(10.0)
+                .sym(0.1)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build();
+    let result = calc::six_sigma(&normal, 3.0).unwrap();
+    assert_eq!(result.dim.desc, "(Normal approximation of the assembly)");
+}
@@ -0,0 +1,70 @@
+use dimstack_rs::error::Error;
+use dimstack_rs::{calc, dim, dist};
+
+fn shaft() -> dim::Basic {
+    dim::Basic::builder("a")
+        .desc("Shaft")
+        .nominal(208.0)
+        .sym(0.036)
+        .build()
+}
+
+#[test]
+fn test_mrss_too_few_dims() {
+    let stack = dim::Stack::new("single".to_owned(), "".to_owned(), vec![shaft().into()]);
+    assert_eq!(
+        calc::mrss(&stack).err(),
+        Some(Error::TooFewDims {
+            analysis: "MRSS",
+            required: 2,
+            found: 1
+        })
+    );
+
+    let stack = dim::Stack::<f64>::new("empty".to_owned(), "".to_owned(), vec![]);
+    assert!(calc::mrss(&stack).is_err());
+    assert!(calc::six_sigma(&stack, 4.5).is_err());
+    assert!(calc::monte_carlo(&stack, 4.5, 1000, 0).is_err());
+}
+
+#[test]
+fn test_zero_process_sigma() {
+    assert_eq!(
+        dim::Reviewed::of_basic(shaft(), 0.0).err(),
+        Some(Error::InvalidProcessSigma(0.0))
+    );
+    assert!(dim::Reviewed::of_basic(shaft(), 6.0).is_ok());
+}
+
+#[test]
+fn test_requirement_inverted_limits() {
+    let normal = dist::Normal::new(0.4, 0.06).unwrap();
+    let spec = dim::Requirement::new(
+        "spec".to_owned(),
+        "".to_owned(),
+        dist::Distribution::Normal(normal),
+        0.8,
+        0.05,
+    );
+    assert_eq!(
+        spec.err(),
+        Some(Error::InvertedLimits {
+            lower: 0.8,
+            upper: 0.05
+        })
+    );
+}
+
+#[test]
+fn test_six_sigma_missing_process_data() {
+    let stack = dim::Stack::builder("unreviewed")
+        .push(shaft().clone())
+        .push(dim::Basic::builder("e").nominal(-200.0).sym(0.145))
+        .build();
+    assert_eq!(
+        calc::six_sigma(&stack, 4.5).err(),
+        Some(Error::MissingProcessData("a".to_owned()))
+    );
+    // design-only stacks still run through Monte Carlo
+    assert!(calc::monte_carlo(&stack, 4.5, 1000, 0).is_ok());
+}
@@ -0,0 +1,79 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::fastener::{Fixed, Floating, Threaded};
+
+#[test]
+fn test_fastener_floating() {
+    let bolt: Floating = Floating::new(0.25).unwrap();
+    assert!((bolt.hole_mmc(0.02) - 0.27).abs() < 1e-12);
+    assert!((bolt.position(0.27).unwrap() - 0.02).abs() < 1e-12);
+    assert_eq!(bolt.position(0.25).err(), Some(Error::InvalidZone(0.0)));
+
+    let hole = bolt.hole("bolt hole", 0.02, 0.01).unwrap();
+    assert!((hole.absolute_lower() - 0.27).abs() < 1e-12);
+    assert!((hole.absolute_upper() - 0.28).abs() < 1e-12);
+}
+
+#[test]
+fn test_fastener_fixed() {
+    let screw: Fixed = Fixed::new(0.25, Threaded::Projected).unwrap();
+    assert!((screw.hole_mmc(0.01, 0.01) - 0.27).abs() < 1e-12);
+    assert!((screw.position(0.27).unwrap() - 0.01).abs() < 1e-12);
+
+    // without a projected zone, T2 counts (1 + 2 * 0.5 / 0.5) = 3 times
+    let screw: Fixed = Fixed::new(
+        0.25,
+        Threaded::Unprojected {
+            projection: 0.5,
+            engagement: 0.5,
+        },
+    )
+    .unwrap();
+    assert!((screw.hole_mmc(0.01, 0.01) - 0.29).abs() < 1e-12);
+    assert!((screw.position(0.27).unwrap() - 0.005).abs() < 1e-12);
+}
+
+#[test]
+fn test_fastener_in_stack() {
+    // radial clearance between a screw and its clearance hole
+    let screw: Fixed = Fixed::new(6.0, Threaded::Projected).unwrap();
+    let mut hole = screw.hole("hole", 0.2, 0.2, 0.1).unwrap();
+    hole.a = 0.5;
+    let stack = dim::Stack::builder("clearance")
+        .push(hole)
+        .push(
+            dim::Basic::builder("screw")
+                .nominal(-6.0)
+                .unequal(0.0, -0.1)
+                .sensitivity(0.5),
+        )
+        .build();
+    let wc = calc::wc(&stack);
+    assert!((wc.absolute_lower() - 0.2).abs() < 1e-12);
+}
+
+#[test]
+fn test_fastener_invalid() {
+    assert_eq!(Floating::new(-0.25).err(), Some(Error::InvalidZone(-0.25)));
+    assert_eq!(
+        Fixed::new(
+            0.25,
+            Threaded::Unprojected {
+                projection: 0.5,
+                engagement: 0.0,
+            },
+        )
+        .err(),
+        Some(Error::InvalidZone(0.0))
+    );
+    let bolt: Floating = Floating::new(0.25).unwrap();
+    assert_eq!(
+        bolt.hole("bolt hole", -0.02, 0.01).err(),
+        Some(Error::InvalidZone(-0.02))
+    );
+    assert!(matches!(
+        bolt.hole("bolt hole", 0.02, -0.01),
+        Err(Error::InvertedLimits { .. })
+    ));
+}
@@ -0,0 +1,128 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::gdt::{Characteristic, FeatureKind, FeatureOfSize, Geometric, Modifier};
+
+#[test]
+fn test_gdt_position_equivalent() {
+    let position: Geometric =
+        Geometric::new("hole position", Characteristic::Position, 0.2).unwrap();
+    let basic = position.basic();
+    assert_eq!(basic.absolute_lower(), -0.1);
+    assert_eq!(basic.absolute_upper(), 0.1);
+
+    // the radial error stays inside the zone as often as +/- 3 sigma does
+    let rayleigh = position.radial_error().unwrap();
+    assert!((rayleigh.cdf(0.1) - 0.9973).abs() < 1e-4);
+    let reviewed = position.reviewed().unwrap();
+    let std_dev = reviewed.distribution.unwrap().std_dev();
+    assert!((std_dev - rayleigh.sigma).abs() < 1e-15);
+    assert!((reviewed.target_process_sigma - 3.4393).abs() < 1e-4);
+}
+
+#[test]
+fn test_gdt_planar_equivalent() {
+    let flatness: Geometric = Geometric::new("flatness", Characteristic::Flatness, 0.05)
+        .unwrap()
+        .sensitivity(-1.0);
+    assert!(flatness.radial_error().is_none());
+    let reviewed = flatness.reviewed().unwrap();
+    assert_eq!(reviewed.target_process_sigma, 3.0);
+    assert!((reviewed.distribution.unwrap().std_dev() - 0.05 / 6.0).abs() < 1e-15);
+    assert_eq!(flatness.basic().a, -1.0);
+}
+
+#[test]
+fn test_gdt_in_stack() {
+    let stack: dim::Stack = dim::Stack::builder("housing")
+        .push(dim::Basic::builder("wall").nominal(10.0).sym(0.1))
+        .push(
+            Geometric::new("profile", Characteristic::Profile, 0.2)
+                .unwrap()
+                .reviewed()
+                .unwrap(),
+        )
+        .push(
+            Geometric::new("runout", Characteristic::Runout, 0.06)
+                .unwrap()
+                .reviewed()
+                .unwrap(),
+        )
+        .build();
+    let wc = calc::wc(&stack);
+    assert!((wc.tol.upper - 0.23).abs() < 1e-12);
+    assert!((wc.nom.x - 10.0).abs() < 1e-12);
+    let mc = calc::monte_carlo(&stack, 3.0, 20_000, 3).unwrap();
+    assert!((mc.distribution.unwrap().mean() - 10.0).abs() < 0.01);
+
+    assert_eq!(
+        Geometric::new("bad", Characteristic::Position, -0.1).err(),
+        Some(Error::InvalidZone(-0.1))
+    );
+    let mut edited: Geometric = Geometric::new("edited", Characteristic::Flatness, 0.1).unwrap();
+    edited.zone = 0.0;
+    assert_eq!(edited.reviewed().err(), Some(Error::InvalidZone(0.0)));
+}
+
+fn hole(modifier: Modifier) -> FeatureOfSize {
+    // the edge of a hole, located by its axis and offset by its radius
+    FeatureOfSize::new(
+        "hole edge",
+        FeatureKind::Internal,
+        dim::Basic::builder("hole")
+            .nominal(10.0)
+            .unequal(0.1, 0.0)
+            .sensitivity(0.5),
+        Geometric::new("hole position", Characteristic::Position, 0.2).unwrap(),
+        modifier,
+    )
+}
+
+#[test]
+fn test_gdt_bonus_tolerance() {
+    let mmc = hole(Modifier::Mmc);
+    assert_eq!(mmc.mmc(), 10.0);
+    assert!((mmc.bonus(10.05) - 0.05).abs() < 1e-12);
+    assert_eq!(mmc.bonus(9.9), 0.0);
+    assert!((hole(Modifier::Lmc).zone(10.0) - 0.3).abs() < 1e-12);
+    assert_eq!(hole(Modifier::Rfs).zone(10.1), 0.2);
+
+    // at the largest hole the zone grows to 0.3
+    let wc = mmc.basic();
+    assert!((wc.absolute_lower() - 4.9).abs() < 1e-12);
+    assert!((wc.absolute_upper() - 5.2).abs() < 1e-12);
+    let rfs = hole(Modifier::Rfs).basic();
+    assert!((rfs.absolute_upper() - 5.15).abs() < 1e-12);
+}
+
+#[test]
+fn test_gdt_bonus_in_stack() {
+    let stack = dim::Stack::builder("edge distance")
+        .push(dim::Basic::builder("plate").nominal(20.0).sym(0.05))
+        .push(hole(Modifier::Mmc).reviewed().unwrap())
+        .build();
+    let wc = calc::wc(&stack);
+    assert!((wc.tol.t() - 0.4).abs() < 1e-12);
+
+    // bonus widens the location error as the hole grows, so the
+    // contribution is wider than at RFS
+    let mmc = hole(Modifier::Mmc)
+        .reviewed()
+        .unwrap()
+        .distribution
+        .unwrap();
+    let rfs = hole(Modifier::Rfs)
+        .reviewed()
+        .unwrap()
+        .distribution
+        .unwrap();
+    assert!((mmc.mean() - 5.025).abs() < 1e-6);
+    assert!(mmc.std_dev() > rfs.std_dev());
+    let size: f64 = 0.5 * 0.1 / 6.0;
+    let location = 0.1 / 3.4393;
+    assert!((rfs.std_dev() - (size * size + location * location).sqrt()).abs() < 1e-4);
+
+    let mc = calc::monte_carlo(&stack, 3.0, 50_000, 4).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.mean() - 25.025).abs() < 0.002);
+}
@@ -0,0 +1,143 @@
+use dimstack_rs::loop2d::{Loop2D, Vector2D};
+use dimstack_rs::{calc, dim, dist};
+use std::f64::consts::PI;
+
+fn angle(name: &str, degrees: f64, tol_degrees: f64) -> dim::Basic {
+    dim::Basic::builder(name)
+        .nominal(degrees.to_radians())
+        .sym(tol_degrees.to_radians())
+        .build()
+}
+
+#[test]
+fn test_loop2d_inclined() {
+    let lp = Loop2D::builder("hinge")
+        .push(Vector2D::new(
+            dim::Basic::builder("L").nominal(10.0).sym(0.1),
+            angle("theta", 30.0, 1.0),
+        ))
+        .build();
+
+    assert!((lp.nominal() - 10.0 * (PI / 6.0).cos()).abs() < 1e-12);
+    let a = lp.sensitivities();
+    assert_eq!(a[0].0, "L");
+    assert!((a[0].1 - (PI / 6.0).cos()).abs() < 1e-12);
+    assert_eq!(a[1].0, "theta");
+    assert!((a[1].1 + 5.0).abs() < 1e-12);
+
+    let wc = lp.wc();
+    let t_wc = 0.1 * (PI / 6.0).cos() + 5.0 * 1f64.to_radians();
+    assert!((wc.tol.t() / 2.0 - t_wc).abs() < 1e-12);
+    assert!((wc.absolute_upper() - (lp.nominal() + t_wc)).abs() < 1e-12);
+
+    let rss = lp.rss().unwrap();
+    let t_rss = ((0.1 * (PI / 6.0).cos()).powi(2) + (5.0 * 1f64.to_radians()).powi(2)).sqrt();
+    assert!((rss.tol.t() / 2.0 - t_rss).abs() < 1e-12);
+
+    // inputs are +/- 3 sigma, so the exact loop is close to RSS / 3
+    let mc = lp.monte_carlo(3.0, 50_000, 1).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.mean() - lp.nominal()).abs() < 0.002);
+    assert!((mc_dist.std_dev() - t_rss / 3.0).abs() < 0.002);
+}
+
+#[test]
+fn test_loop2d_matches_1d() {
+    // colinear vectors reduce to an ordinary stack
+    let lp = Loop2D::builder("colinear")
+        .push(Vector2D::new(
+            dim::Basic::builder("a").nominal(20.0).unequal(0.1, -0.05),
+            angle("a angle", 0.0, 0.0),
+        ))
+        .push(Vector2D::new(
+            dim::Basic::builder("b").nominal(15.0).sym(0.02),
+            angle("b angle", 180.0, 0.0),
+        ))
+        .build();
+    let stack = dim::Stack::builder("colinear")
+        .push(dim::Basic::builder("a").nominal(20.0).unequal(0.1, -0.05))
+        .push(dim::Basic::builder("b").nominal(-15.0).sym(0.02))
+        .build();
+
+    let wc = calc::wc(&stack);
+    assert!((lp.wc().nom.x - wc.nom.x).abs() < 1e-12);
+    assert!((lp.wc().tol.t() - wc.tol.t()).abs() < 1e-12);
+    let rss = calc::rss(&stack).unwrap();
+    assert!((lp.rss().unwrap().tol.t() - rss.tol.t()).abs() < 1e-12);
+}
+
+#[test]
+fn test_loop2d_zero_nominal_angle() {
+    // an angle is signed, so 0 +0.002/-0 lies between 0 and 0.002 rad
+    let lp = Loop2D::builder("lever")
+        .direction(PI / 2.0)
+        .push(Vector2D::new(
+            dim::Basic::builder("L").nominal(10.0).sym(0.0),
+            dim::Basic::builder("theta")
+                .nominal(0.0)
+                .unequal(0.002, 0.0),
+        ))
+        .build();
+    assert!((lp.nominal() - 10.0 * 0.001f64.sin()).abs() < 1e-12);
+    let a = lp.sensitivities();
+    assert!((a[1].1 - 10.0 * 0.001f64.cos()).abs() < 1e-12);
+
+    let mc = lp.monte_carlo(3.0, 50_000, 3).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.mean() - lp.nominal()).abs() < 1e-4);
+}
+
+#[test]
+fn test_loop2d_correlation() {
+    let mut lp = Loop2D::builder("colinear")
+        .push(Vector2D::new(
+            dim::Basic::builder("a").nominal(20.0).sym(0.1),
+            angle("a angle", 0.0, 0.0),
+        ))
+        .push(Vector2D::new(
+            dim::Basic::builder("b").nominal(15.0).sym(0.1),
+            angle("b angle", 180.0, 0.0),
+        ))
+        .build();
+    lp.correlate("a", "b", 0.5).unwrap();
+    let mut stack = dim::Stack::builder("colinear")
+        .push(dim::Basic::builder("a").nominal(20.0).sym(0.1))
+        .push(dim::Basic::builder("b").nominal(-15.0).sym(0.1))
+        .build();
+    stack.correlate("a", "b", 0.5).unwrap();
+
+    let t_rss = calc::rss(&stack).unwrap().tol.t() / 2.0;
+    assert!((lp.rss().unwrap().tol.t() / 2.0 - t_rss).abs() < 1e-12);
+    let mc = lp.monte_carlo(3.0, 100_000, 5).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.std_dev() / (t_rss / 3.0) - 1.0).abs() < 0.02);
+    assert!(lp.correlate("a", "c", 0.5).is_err());
+}
+
+#[test]
+fn test_loop2d_measured_negative_angle() {
+    // measured angles are signed already, so they are sampled as given
+    let samples = vec![-0.0095, -0.009, -0.0085];
+    let theta: dim::Reviewed = dim::Basic::builder("theta")
+        .nominal(-0.01)
+        .unequal(0.002, 0.0)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Empirical(
+            dist::Empirical::new(samples.clone()).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    let lp = Loop2D::builder("lever")
+        .direction(PI / 2.0)
+        .push(Vector2D::new(
+            dim::Basic::builder("L").nominal(10.0).sym(0.0),
+            theta,
+        ))
+        .build();
+    assert!((lp.nominal() - 10.0 * (-0.009f64).sin()).abs() < 1e-12);
+
+    let mc = lp.monte_carlo(3.0, 50_000, 3).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    let expected = samples.iter().map(|&s| 10.0 * s.sin()).sum::<f64>() / 3.0;
+    assert!((mc_dist.mean() - expected).abs() < 1e-3);
+}
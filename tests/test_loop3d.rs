@@ -0,0 +1,130 @@
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+use dimstack_rs::loop3d::{Axis, Loop3D};
+
+#[test]
+fn test_loop3d_tilted_face() {
+    // a wall 100 along x that may tilt about z, with the gap measured 50 up the wall
+    let lp: Loop3D = Loop3D::builder("enclosure")
+        .translate(Axis::X, dim::Basic::builder("base").nominal(100.0).sym(0.1))
+        .rotate(Axis::Z, dim::Basic::builder("tilt").nominal(0.0).sym(0.001))
+        .translate(
+            Axis::Y,
+            dim::Basic::builder("height").nominal(50.0).sym(0.05),
+        )
+        .build()
+        .unwrap();
+
+    assert!((lp.nominal() - 100.0).abs() < 1e-12);
+    let a = lp.sensitivities();
+    assert_eq!(a[0].0, "base");
+    assert!((a[0].1 - 1.0).abs() < 1e-12);
+    assert!((a[1].1 + 50.0).abs() < 1e-12);
+    assert!(a[2].1.abs() < 1e-12);
+
+    let wc = lp.wc();
+    assert!((wc.tol.t() / 2.0 - 0.15).abs() < 1e-12);
+    let rss = lp.rss().unwrap();
+    let t_rss = (0.1f64.powi(2) + 0.05f64.powi(2)).sqrt();
+    assert!((rss.tol.t() / 2.0 - t_rss).abs() < 1e-12);
+
+    let mc = lp.monte_carlo(3.0, 50_000, 2).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.mean() - 100.0).abs() < 0.001);
+    assert!((mc_dist.std_dev() - t_rss / 3.0).abs() < 0.001);
+}
+
+#[test]
+fn test_loop3d_direction_and_point() {
+    // rotating about y by 30 degrees swings the end point (0, 0, 40) towards x
+    let theta = 30f64.to_radians();
+    let lp: Loop3D = Loop3D::builder("bracket")
+        .rotate(
+            Axis::Y,
+            dim::Basic::builder("angle").nominal(theta).sym(0.002),
+        )
+        .point([0.0, 0.0, 40.0])
+        .direction([2.0, 0.0, 0.0])
+        .build()
+        .unwrap();
+    assert!((lp.nominal() - 40.0 * theta.sin()).abs() < 1e-12);
+    let a = lp.sensitivities();
+    assert!((a[0].1 - 40.0 * theta.cos()).abs() < 1e-12);
+}
+
+#[test]
+fn test_loop3d_zero_nominal_rotation() {
+    // a rotation is signed, so 0 +0.002/-0 lies between 0 and 0.002 rad
+    let lp: Loop3D = Loop3D::builder("tilt")
+        .rotate(
+            Axis::Z,
+            dim::Basic::builder("tilt").nominal(0.0).unequal(0.002, 0.0),
+        )
+        .translate(
+            Axis::Y,
+            dim::Basic::builder("height").nominal(50.0).sym(0.0),
+        )
+        .build()
+        .unwrap();
+    assert!((lp.nominal() + 50.0 * 0.001f64.sin()).abs() < 1e-12);
+
+    let mc = lp.monte_carlo(3.0, 50_000, 4).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.mean() - lp.nominal()).abs() < 1e-4);
+}
+
+#[test]
+fn test_loop3d_correlation() {
+    let mut lp: Loop3D = Loop3D::builder("pair")
+        .translate(Axis::X, dim::Basic::builder("base").nominal(100.0).sym(0.1))
+        .translate(
+            Axis::X,
+            dim::Basic::builder("spacer").nominal(20.0).sym(0.1),
+        )
+        .build()
+        .unwrap();
+    lp.correlate("base", "spacer", 1.0).unwrap();
+    assert!((lp.rss().unwrap().tol.t() / 2.0 - 0.2).abs() < 1e-12);
+    let mc = lp.monte_carlo(3.0, 100_000, 6).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    assert!((mc_dist.std_dev() / (0.2 / 3.0) - 1.0).abs() < 0.02);
+}
+
+#[test]
+fn test_loop3d_measured_negative_rotation() {
+    // measured rotations are signed already, so they are sampled as given
+    let samples = vec![-0.0095, -0.009, -0.0085];
+    let tilt: dim::Reviewed = dim::Basic::builder("tilt")
+        .nominal(-0.01)
+        .unequal(0.002, 0.0)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Empirical(
+            dist::Empirical::new(samples.clone()).unwrap(),
+        ))
+        .build()
+        .unwrap();
+    let lp: Loop3D = Loop3D::builder("tilt")
+        .rotate(Axis::Z, tilt)
+        .translate(
+            Axis::Y,
+            dim::Basic::builder("height").nominal(50.0).sym(0.0),
+        )
+        .build()
+        .unwrap();
+    assert!((lp.nominal() + 50.0 * (-0.009f64).sin()).abs() < 1e-12);
+
+    let mc = lp.monte_carlo(3.0, 50_000, 4).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    let expected = samples.iter().map(|&s| -50.0 * s.sin()).sum::<f64>() / 3.0;
+    assert!((mc_dist.mean() - expected).abs() < 1e-3);
+}
+
+#[test]
+fn test_loop3d_zero_direction() {
+    let lp: Result<Loop3D, Error> = Loop3D::builder("bracket")
+        .translate(Axis::X, dim::Basic::builder("base").nominal(100.0).sym(0.1))
+        .direction([0.0, 0.0, 0.0])
+        .build();
+    assert_eq!(lp.err(), Some(Error::InvalidDirection([0.0, 0.0, 0.0])));
+}
@@ -0,0 +1,66 @@
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::material::{Library, Material};
+use std::io;
+use std::path::Path;
+
+#[test]
+fn test_material_builtin() {
+    let al: Material = Material::named("6061-T6").unwrap();
+    assert_eq!(al.cte, 23.6e-6);
+    assert_eq!(al.modulus, 68_900.0);
+    // names ignore case
+    let library: Library = Library::builtin();
+    assert_eq!(library.get("abs").unwrap().name, "ABS");
+    assert_eq!(
+        Material::<f64>::named("unobtainium").err(),
+        Some(Error::UnknownMaterial("unobtainium".to_owned()))
+    );
+
+    let case: dim::Basic = dim::Basic::builder("case")
+        .nominal(100.0)
+        .material(al)
+        .build();
+    assert_eq!(case.material.unwrap().name, "6061-T6");
+}
+
+#[test]
+fn test_material_library_from_file() {
+    let path = std::env::temp_dir().join("dimstack_test_materials.csv");
+    std::fs::write(
+        &path,
+        "# name, cte, modulus\n\nInvar 36, 1.2e-6, 141000\n6061-T6, 23.4e-6, 69000\n",
+    )
+    .unwrap();
+    let mut library: Library = Library::builtin();
+    let count = library.materials().len();
+    library.load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // one new material, one replaced
+    assert_eq!(library.materials().len(), count + 1);
+    assert_eq!(library.material("invar 36").unwrap().cte, 1.2e-6);
+    assert_eq!(library.material("6061-T6").unwrap().cte, 23.4e-6);
+
+    let mut library: Library = Library::new();
+    library.insert(Material::new("glass", 9e-6, 70_000.0));
+    assert_eq!(library.materials().len(), 1);
+    assert_eq!(
+        library.extend_from_str("steel, 12e-6\n"),
+        Err(Error::InvalidMaterialLine {
+            line: 1,
+            text: "steel, 12e-6".to_owned()
+        })
+    );
+    let err = library.load("/nonexistent/materials.csv").unwrap_err();
+    match &err {
+        Error::Io { path, source } => {
+            assert_eq!(path, Path::new("/nonexistent/materials.csv"));
+            assert_eq!(source.kind(), io::ErrorKind::NotFound);
+        }
+        _ => panic!("Expected an Io error"),
+    }
+    assert!(std::error::Error::source(&err).is_some());
+    assert_eq!(err.clone(), err);
+    assert!(err.to_string().starts_with("/nonexistent/materials.csv: "));
+}
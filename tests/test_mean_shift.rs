@@ -0,0 +1,105 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+
+fn stack() -> dim::Stack {
+    dim::Stack::builder("gap")
+        .push(dim::Basic::builder("housing").nominal(20.0).sym(0.3))
+        .push(dim::Basic::builder("bearing").nominal(-8.0).sym(0.4))
+        .push(
+            dim::Basic::builder("shaft")
+                .nominal(-11.0)
+                .unequal(0.0, -0.2),
+        )
+        .build()
+}
+
+fn reviewed(name: &str, nominal: f64, tol: f64, sigma: f64, skew: f64) -> dim::Reviewed {
+    dim::Basic::builder(name)
+        .nominal(nominal)
+        .sym(tol)
+        .reviewed(sigma)
+        .skew(skew)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_bender() {
+    let stack = stack();
+    let rss = calc::rss(&stack).unwrap();
+    let bender = calc::bender(&stack).unwrap();
+    assert_eq!(bender.nom.x, rss.nom.x);
+    assert!((bender.tol.t() - 1.5 * rss.tol.t()).abs() < 1e-12);
+    assert!((bender.tol.t() / 2.0 - 1.5 * 0.26f64.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn test_mean_shift_rss() {
+    let stack = stack();
+    let rss = calc::rss(&stack).unwrap();
+    let wc = calc::wc(&stack);
+
+    let none = calc::mean_shift_rss(&stack, 0.0).unwrap();
+    assert!((none.nom.x - rss.nom.x).abs() < 1e-12);
+    assert!((none.tol.t() - rss.tol.t()).abs() < 1e-12);
+    let full = calc::mean_shift_rss(&stack, 1.0).unwrap();
+    assert!((full.tol.t() - wc.tol.t()).abs() < 1e-12);
+
+    let shifted = calc::mean_shift_rss(&stack, 0.2).unwrap();
+    let expected = 0.2 * 0.8 + 0.8 * 0.26f64.sqrt();
+    assert!((shifted.tol.t() / 2.0 - expected).abs() < 1e-12);
+    assert!((shifted.median() - 1.1).abs() < 1e-12);
+
+    assert_eq!(
+        calc::mean_shift_rss(&stack, 1.5).err(),
+        Some(Error::InvalidShift(1.5))
+    );
+    assert!(calc::mean_shift_rss(&stack, f64::NAN).is_err());
+}
+
+#[test]
+fn test_swc() {
+    // centered at the target sigma, no mean can shift: SWC is six sigma
+    let centered: dim::Stack = dim::Stack::builder("centered")
+        .push(reviewed("a", 20.0, 0.3, 3.0, 0.0))
+        .push(reviewed("b", -8.0, 0.4, 3.0, 0.0))
+        .build();
+    let swc = calc::swc(&centered, 3.0).unwrap();
+    let six_sigma = calc::six_sigma(&centered, 3.0).unwrap();
+    assert!((swc.tol.t() - six_sigma.dim.tol.t()).abs() < 1e-12);
+    assert!((swc.nom.x - 12.0).abs() < 1e-12);
+
+    // a 4 sigma process may drift by a sigma each way at a 3 sigma target
+    let capable: dim::Stack = dim::Stack::builder("capable")
+        .push(
+            dim::Basic::builder("a")
+                .nominal(20.0)
+                .sym(0.4)
+                .reviewed(3.0)
+                .distribution(dimstack_rs::dist::Distribution::Normal(
+                    dimstack_rs::dist::Normal::new(20.0, 0.1).unwrap(),
+                ))
+                .build()
+                .unwrap(),
+        )
+        .push(reviewed("b", -8.0, 0.3, 3.0, 0.0))
+        .build();
+    let swc = calc::swc(&capable, 3.0).unwrap();
+    let expected = 0.1 + 3.0 * (0.01f64 + 0.01).sqrt();
+    assert!((swc.tol.t() / 2.0 - expected).abs() < 1e-12);
+
+    // a known shift beyond the allowed one is used as is
+    let skewed: dim::Stack = dim::Stack::builder("skewed")
+        .push(reviewed("a", 20.0, 0.3, 3.0, 0.5))
+        .push(reviewed("b", -8.0, 0.3, 3.0, 0.0))
+        .build();
+    let swc = calc::swc(&skewed, 3.0).unwrap();
+    let expected = 0.15 + 3.0 * (0.01f64 + 0.01).sqrt();
+    assert!((swc.tol.t() / 2.0 - expected).abs() < 1e-12);
+
+    assert_eq!(
+        calc::swc(&stack(), 3.0).err(),
+        Some(Error::MissingProcessData("housing".to_owned()))
+    );
+}
@@ -0,0 +1,145 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+use dimstack_rs::nonlinear::NonlinearStack;
+
+fn v_groove() -> NonlinearStack {
+    // height of a ball's center above the vertex of a V-groove
+    NonlinearStack::builder("ball in groove", |x: &[f64]| {
+        x[0] / 2.0 / (x[1] / 2.0).sin()
+    })
+    .push(dim::Basic::builder("ball diameter").nominal(10.0).sym(0.02))
+    .push(
+        dim::Basic::builder("groove angle")
+            .nominal(90f64.to_radians())
+            .sym(0.5f64.to_radians()),
+    )
+    .build()
+}
+
+#[test]
+fn test_nonlinear_sensitivities() {
+    let stack = v_groove();
+    let half = 45f64.to_radians();
+    assert!((stack.nominal() - 5.0 / half.sin()).abs() < 1e-12);
+
+    let a = stack.sensitivities();
+    assert_eq!(a[1].0, "groove angle");
+    assert!((a[0].1 - 0.5 / half.sin()).abs() < 1e-8);
+    assert!((a[1].1 + 10.0 * half.cos() / (4.0 * half.sin().powi(2))).abs() < 1e-7);
+
+    let h = stack.hessian();
+    assert!(h[0][0].abs() < 1e-4);
+    assert!((h[0][1] - h[1][0]).abs() < 1e-12);
+    assert!((h[0][1] + half.cos() / (4.0 * half.sin().powi(2))).abs() < 1e-4);
+}
+
+#[test]
+fn test_nonlinear_wc_vertex_search() {
+    let stack = v_groove();
+    let wc = stack.wc();
+    let lower = stack.gap(&[9.98, 90.5f64.to_radians()]);
+    let upper = stack.gap(&[10.02, 89.5f64.to_radians()]);
+    assert!((wc.absolute_lower() - lower).abs() < 1e-12);
+    assert!((wc.absolute_upper() - upper).abs() < 1e-12);
+    assert!((wc.absolute_nominal_adj() - stack.nominal()).abs() < 1e-12);
+}
+
+#[test]
+fn test_nonlinear_second_order() {
+    let stack = v_groove();
+    let rss = stack.rss().unwrap();
+    let rss2 = stack.rss2().unwrap();
+    // the groove curves upward, so the mean shifts up
+    assert!(rss2.absolute_median() > rss.absolute_median());
+    assert!(rss2.tol.t() >= rss.tol.t());
+
+    let mc = stack.monte_carlo(3.0, 100_000, 5).unwrap();
+    let mc_dist = mc.distribution.unwrap();
+    let shift = rss2.absolute_median() - stack.nominal();
+    assert!((mc_dist.mean() - stack.nominal() - shift).abs() < shift.abs());
+    assert!((mc_dist.std_dev() - rss2.tol.t() / 6.0).abs() < 1e-4);
+}
+
+#[test]
+fn test_nonlinear_matches_linear_stack() {
+    let a: dim::Basic = dim::Basic::builder("a")
+        .nominal(20.0)
+        .unequal(0.1, 0.05)
+        .build();
+    let b: dim::Basic = dim::Basic::builder("b").nominal(-8.0).sym(0.2).build();
+    let linear = dim::Stack::builder("linear")
+        .push(a.clone())
+        .push(b.clone())
+        .build();
+    let stack = NonlinearStack::builder("linear", |x: &[f64]| x[0] + x[1])
+        .push(a)
+        .push(b)
+        .build();
+
+    let wc = stack.wc();
+    let calc_wc = calc::wc(&linear);
+    assert!((wc.absolute_lower() - calc_wc.absolute_lower()).abs() < 1e-12);
+    assert!((wc.absolute_upper() - calc_wc.absolute_upper()).abs() < 1e-12);
+    let rss = stack.rss().unwrap();
+    let calc_rss = calc::rss(&linear).unwrap();
+    assert!((rss.tol.t() - calc_rss.tol.t()).abs() < 1e-9);
+    assert!((stack.rss2().unwrap().tol.t() - calc_rss.tol.t()).abs() < 1e-6);
+}
+
+#[test]
+fn test_nonlinear_method_of_moments() {
+    // a square of a normal dim has an exact second order expansion
+    let stack = NonlinearStack::builder("area", |x: &[f64]| x[0] * x[0])
+        .push(dim::Basic::builder("side").nominal(1.0).sym(0.3))
+        .build();
+    let s: f64 = 0.1;
+    let moments = stack.moments().unwrap();
+    assert!((moments.mean - (1.0 + s * s)).abs() < 1e-6);
+    assert!((moments.variance - (4.0 * s * s + 2.0 * s.powi(4))).abs() < 1e-6);
+    // 8 s^4 (3 + s^2) / variance^1.5
+    let skewness = (8.0 * s.powi(4) * (3.0 + s * s)) / moments.variance.powf(1.5);
+    assert!((moments.skewness - skewness).abs() < 1e-3);
+
+    let mom = stack.method_of_moments(3.0).unwrap();
+    // limits at +/- 3 sigma of the side
+    assert!((mom.dim.absolute_lower() - 0.7 * 0.7).abs() < 2e-3);
+    assert!((mom.dim.absolute_upper() - 1.3 * 1.3).abs() < 2e-3);
+
+    let requirement = dim::Requirement::new(
+        "area".to_owned(),
+        "".to_owned(),
+        mom.distribution.unwrap(),
+        0.6,
+        1.5,
+    )
+    .unwrap();
+    let side = dist::Normal::new(1.0, s).unwrap();
+    let exact = side.cdf(0.6f64.sqrt()) + side.sf(1.5f64.sqrt());
+    assert!((requirement.yield_loss_probability() / exact - 1.0).abs() < 0.01);
+}
+
+#[test]
+fn test_nonlinear_correlation() {
+    let mut stack = NonlinearStack::builder("linear", |x: &[f64]| x[0] - x[1])
+        .push(dim::Basic::builder("a").nominal(20.0).sym(0.1))
+        .push(dim::Basic::builder("b").nominal(15.0).sym(0.1))
+        .build();
+    stack.correlate("a", "b", 0.5).unwrap();
+    let mut linear = dim::Stack::builder("linear")
+        .push(dim::Basic::builder("a").nominal(20.0).sym(0.1))
+        .push(dim::Basic::builder("b").nominal(-15.0).sym(0.1))
+        .build();
+    linear.correlate("a", "b", 0.5).unwrap();
+    assert!((stack.rss().unwrap().tol.t() - calc::rss(&linear).unwrap().tol.t()).abs() < 1e-6);
+
+    assert_eq!(
+        stack.rss2().err(),
+        Some(Error::CorrelatedDims("Second Order RSS"))
+    );
+    assert_eq!(
+        stack.method_of_moments(3.0).err(),
+        Some(Error::CorrelatedDims("Method of Moments"))
+    );
+}
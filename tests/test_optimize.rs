@@ -0,0 +1,94 @@
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+use dimstack_rs::optimize::{self, Range};
+
+fn stack() -> dim::Stack {
+    dim::Stack::builder("gap")
+        .push(
+            dim::Basic::builder("housing")
+                .nominal(20.0)
+                .sym(0.1)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(dim::Basic::builder("sleeve").nominal(-8.0).sym(0.1))
+        .build()
+}
+
+fn requirement() -> dim::Requirement {
+    dim::Requirement::new(
+        "gap".to_owned(),
+        "".to_owned(),
+        dist::Distribution::Normal(dist::Normal::new(12.0, 1.0).unwrap()),
+        11.85,
+        12.25,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_recenter_one_dim() {
+    let ranges = [Range::symmetric("sleeve", 0.1).unwrap()];
+    let result = optimize::recenter(&stack(), &requirement(), &ranges).unwrap();
+    assert_eq!(result.shifts.len(), 1);
+    assert_eq!(result.shifts[0].0, "sleeve");
+    // a shorter sleeve opens the gap to the middle of the requirement
+    assert!((result.shifts[0].1 + 0.05).abs() < 1e-6);
+    assert!((result.stack.dims[1].basic().nom.x + 7.95).abs() < 1e-6);
+
+    let std_dev = (2.0f64).sqrt() * 0.1 / 3.0;
+    let normal = dist::Normal::new(0.0, std_dev).unwrap();
+    let before = 1.0 - normal.cdf(-0.15) - normal.sf(0.25);
+    let after = 1.0 - 2.0 * normal.sf(0.2);
+    assert!((result.yield_before - before).abs() < 1e-6);
+    assert!((result.yield_after - after).abs() < 1e-6);
+    assert!(result.yield_after > result.yield_before);
+}
+
+#[test]
+fn test_recenter_split() {
+    // the housing is stopped by its range, the sleeve makes up the rest
+    let ranges = [
+        Range::symmetric("housing", 0.02).unwrap(),
+        Range::symmetric("sleeve", 0.1).unwrap(),
+    ];
+    let result = optimize::recenter(&stack(), &requirement(), &ranges).unwrap();
+    assert!((result.shifts[0].1 - 0.02).abs() < 1e-6);
+    assert!((result.shifts[1].1 + 0.03).abs() < 1e-6);
+    // the reviewed distribution moves with the nominal
+    let mean = result.stack.dims[0]
+        .reviewed()
+        .unwrap()
+        .distribution
+        .as_ref()
+        .unwrap()
+        .mean();
+    assert!((mean - 20.02).abs() < 1e-6);
+
+    // a range too small to reach the middle goes to its end
+    let ranges = [Range::new("sleeve", -0.01, 0.1).unwrap()];
+    let result = optimize::recenter(&stack(), &requirement(), &ranges).unwrap();
+    assert!((result.shifts[0].1 + 0.01).abs() < 1e-9);
+}
+
+#[test]
+fn test_recenter_errors() {
+    assert!(matches!(
+        Range::new("sleeve", 0.1, 0.2),
+        Err(Error::InvertedLimits { .. })
+    ));
+    let ranges = [Range::symmetric("cover", 0.1).unwrap()];
+    assert_eq!(
+        optimize::recenter(&stack(), &requirement(), &ranges).err(),
+        Some(Error::UnknownDim("cover".to_owned()))
+    );
+    let mut correlated = stack();
+    correlated.correlate("housing", "sleeve", 0.5).unwrap();
+    let ranges = [Range::symmetric("sleeve", 0.1).unwrap()];
+    assert_eq!(
+        optimize::recenter(&correlated, &requirement(), &ranges).err(),
+        Some(Error::CorrelatedDims("Recenter"))
+    );
+}
@@ -0,0 +1,89 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::part::{self, Part};
+
+fn housing() -> Part {
+    Part::new("100-2001", "Housing")
+        .revision("C")
+        .supplier("Acme Castings")
+}
+
+fn stack() -> dim::Stack {
+    dim::Stack::builder("end play")
+        .push(
+            dim::Basic::builder("bore depth")
+                .nominal(20.0)
+                .sym(0.3)
+                .part(housing())
+                .feature("bearing bore"),
+        )
+        .push(
+            dim::Basic::builder("bearing width")
+                .nominal(-8.0)
+                .sym(0.4)
+                .part(Part::new("BRG-6204", "Bearing").supplier("SKF")),
+        )
+        .push(
+            dim::Basic::builder("cover step")
+                .nominal(-11.0)
+                .sym(0.3)
+                .part(housing())
+                .feature("cover face"),
+        )
+        .push(dim::Basic::builder("gasket").nominal(0.5).sym(0.0))
+        .build()
+}
+
+#[test]
+fn test_part_builder() {
+    let dim: dim::Basic = dim::Basic::builder("bore depth")
+        .part(housing())
+        .feature("bearing bore")
+        .build();
+    let part = dim.part.unwrap();
+    assert_eq!(part.number, "100-2001");
+    assert_eq!(part.revision, "C");
+    assert_eq!(part.supplier, "Acme Castings");
+    assert_eq!(dim.feature.as_deref(), Some("bearing bore"));
+    let plain: dim::Basic = dim::Basic::builder("gasket").build();
+    assert!(plain.part.is_none() && plain.feature.is_none());
+}
+
+#[test]
+fn test_contributions() {
+    let contributions = calc::contributions(&stack()).unwrap();
+    let wc: Vec<f64> = contributions.iter().map(|c| c.wc).collect();
+    let rss: Vec<f64> = contributions.iter().map(|c| c.rss).collect();
+    for (x, expected) in wc.iter().zip([0.3, 0.4, 0.3, 0.0]) {
+        assert!((x - expected).abs() < 1e-12);
+    }
+    for (x, expected) in rss.iter().zip([0.09 / 0.34, 0.16 / 0.34, 0.09 / 0.34, 0.0]) {
+        assert!((x - expected).abs() < 1e-12);
+    }
+    assert_eq!(contributions[2].feature.as_deref(), Some("cover face"));
+
+    // covariance shares still sum to one with correlation
+    let mut correlated = stack();
+    correlated
+        .correlate("bore depth", "cover step", 0.8)
+        .unwrap();
+    let total: f64 = calc::contributions(&correlated)
+        .unwrap()
+        .iter()
+        .map(|c| c.rss)
+        .sum();
+    assert!((total - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_by_part() {
+    let shares = part::by_part(&stack()).unwrap();
+    assert_eq!(shares.len(), 3);
+    assert_eq!(shares[0].part, Some(housing()));
+    assert_eq!(shares[0].dims, vec!["bore depth", "cover step"]);
+    assert!((shares[0].wc - 0.6).abs() < 1e-12);
+    assert!((shares[0].rss - 0.18 / 0.34).abs() < 1e-12);
+    assert_eq!(shares[1].part.as_ref().unwrap().number, "BRG-6204");
+    assert!(shares[2].part.is_none());
+    assert_eq!(shares[2].dims, vec!["gasket"]);
+}
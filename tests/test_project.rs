@@ -0,0 +1,151 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::project::{Entry, Project, StackRef};
+
+fn housing() -> Project {
+    let mut project = Project::new("housing");
+    project
+        .add_dim(dim::Basic::builder("bore depth").nominal(20.0).sym(0.1))
+        .unwrap();
+    project
+        .add_dim(dim::Basic::builder("bearing width").nominal(8.0).sym(0.05))
+        .unwrap();
+    project
+        .add_dim(
+            dim::Basic::builder("shaft shoulder")
+                .nominal(11.0)
+                .sym(0.05)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+    project
+        .add_stack(
+            StackRef::builder("end play")
+                .push("bore depth")
+                .push_reversed("bearing width")
+                .push_reversed("shaft shoulder")
+                .build(),
+        )
+        .unwrap();
+    project
+        .add_stack(
+            StackRef::builder("bearing protrusion")
+                .desc("bearing face above the bore")
+                .push("bearing width")
+                .push_reversed("bore depth")
+                .push_reversed("bore depth")
+                .build(),
+        )
+        .unwrap();
+    project
+}
+
+#[test]
+fn test_project_resolve() {
+    let project = housing();
+    let stack = project.stack("end play").unwrap();
+    let manual: dim::Stack = dim::Stack::builder("end play")
+        .push(dim::Basic::builder("bore depth").nominal(20.0).sym(0.1))
+        .push(dim::Basic::builder("bearing width").nominal(-8.0).sym(0.05))
+        .push(
+            dim::Basic::builder("shaft shoulder")
+                .nominal(-11.0)
+                .sym(0.05)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build();
+    let rss = |stack: &dim::Stack| calc::rss(stack).unwrap();
+    for analysis in [calc::closed, calc::wc, rss] {
+        let (a, b) = (analysis(&stack), analysis(&manual));
+        assert!((a.nom.x - b.nom.x).abs() < 1e-12);
+        assert!((a.lower() - b.lower()).abs() < 1e-12);
+        assert!((a.upper() - b.upper()).abs() < 1e-12);
+    }
+    let mc = calc::monte_carlo(&stack, 3.0, 2000, 7).unwrap();
+    let manual_mc = calc::monte_carlo(&manual, 3.0, 2000, 7).unwrap();
+    assert!((mc.dim.nom.x - manual_mc.dim.nom.x).abs() < 1e-12);
+
+    assert_eq!(
+        project.users("bore depth"),
+        vec!["end play", "bearing protrusion"]
+    );
+    assert_eq!(project.users("shaft shoulder"), vec!["end play"]);
+    assert!(project.users("cover").is_empty());
+}
+
+#[test]
+fn test_project_edit_updates_every_stack() {
+    let mut project = housing();
+    let before: Vec<dim::Basic> = project.run(calc::wc).unwrap();
+    assert!((before[0].nom.x - 1.0).abs() < 1e-12);
+    assert!((before[1].nom.x + 32.0).abs() < 1e-12);
+
+    project
+        .update_dim(dim::Basic::builder("bore depth").nominal(20.5).sym(0.2))
+        .unwrap();
+    let after = project.run(calc::wc).unwrap();
+    assert!((after[0].nom.x - 1.5).abs() < 1e-12);
+    assert!((after[0].tol.t() - before[0].tol.t() - 0.2).abs() < 1e-12);
+    assert!((after[1].nom.x + 33.0).abs() < 1e-12);
+    assert!((after[1].tol.t() - before[1].tol.t() - 0.4).abs() < 1e-12);
+
+    project.dim_mut("bearing width").unwrap().basic_mut().nom.x = 7.5;
+    let stacks = project.stacks().unwrap();
+    assert!((calc::closed(&stacks[0]).nom.x - 2.0).abs() < 1e-12);
+    assert!((calc::closed(&stacks[1]).nom.x + 33.5).abs() < 1e-12);
+}
+
+#[test]
+fn test_project_entry_options() {
+    let mut project = housing();
+    project
+        .add_stack(
+            StackRef::builder("lever")
+                .entry(Entry::new("bearing width").sensitivity(2.0))
+                .correlate("bearing width", "shaft shoulder", 0.5)
+                .push_reversed("shaft shoulder")
+                .build(),
+        )
+        .unwrap();
+    let stack = project.stack("lever").unwrap();
+    assert!((calc::closed(&stack).nom.x - 5.0).abs() < 1e-12);
+    // the library dim keeps its own sensitivity
+    assert_eq!(project.dim("bearing width").unwrap().basic().a, 1.0);
+    let correlation = stack.correlation().unwrap().unwrap();
+    assert_eq!(correlation.get(0, 1), 0.5);
+}
+
+#[test]
+fn test_project_errors() {
+    let mut project = housing();
+    assert_eq!(
+        project.add_dim(dim::Basic::builder("bore depth").nominal(1.0).sym(0.1)),
+        Err(Error::DuplicateDim("bore depth".to_owned()))
+    );
+    assert_eq!(
+        project.update_dim(dim::Basic::builder("cover").nominal(1.0).sym(0.1)),
+        Err(Error::UnknownDim("cover".to_owned()))
+    );
+    assert_eq!(
+        project.add_stack(StackRef::builder("lid").push("cover").build()),
+        Err(Error::UnknownDim("cover".to_owned()))
+    );
+    assert!(matches!(
+        project.stack("lid"),
+        Err(Error::UnknownStack(name)) if name == "lid"
+    ));
+    project
+        .add_stack(
+            StackRef::builder("bad")
+                .push("bore depth")
+                .correlate("bore depth", "cover", 0.5)
+                .build(),
+        )
+        .unwrap();
+    assert!(project.run(calc::closed).is_err());
+}
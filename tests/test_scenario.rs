@@ -0,0 +1,97 @@
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+use dimstack_rs::scenario::{Comparison, Scenario};
+use dimstack_rs::tol::Bilateral;
+
+fn reviewed(name: &str, nominal: f64, tol: f64) -> dim::Reviewed {
+    dim::Basic::builder(name)
+        .nominal(nominal)
+        .sym(tol)
+        .reviewed(3.0)
+        .build()
+        .unwrap()
+}
+
+fn stack() -> dim::Stack {
+    dim::Stack::builder("bearing")
+        .push(reviewed("shaft", 208.0, 0.036))
+        .push(reviewed("case", -200.0, 0.145))
+        .push(reviewed("ring", -7.75, 0.03))
+        .build()
+}
+
+fn requirement() -> dim::Requirement {
+    dim::Requirement::new(
+        "end play".to_owned(),
+        "".to_owned(),
+        dist::Distribution::Normal(dist::Normal::new(0.25, 0.1).unwrap()),
+        0.1,
+        0.4,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_scenario_apply() {
+    let stack = stack();
+    let tighter = Scenario::new("tighter case").tolerance("case", Bilateral::symmetric(0.1));
+    let variant = tighter.apply(&stack).unwrap();
+    let case = variant.dims[1].reviewed().unwrap();
+    assert_eq!(case.dim.tol.t(), 0.2);
+    // the assumed normal distribution follows the tolerance
+    let std_dev = case.distribution.as_ref().unwrap().std_dev();
+    assert!((std_dev - 0.1 / 3.0).abs() < 1e-12);
+    // the stack itself is untouched
+    assert!((stack.dims[1].basic().tol.t() - 0.29).abs() < 1e-12);
+
+    let uniform = dist::Distribution::Uniform(dist::Uniform::new(-7.78, -7.72).unwrap());
+    let variant = Scenario::new("uniform ring")
+        .distribution("ring", uniform)
+        .process_sigma("shaft", 4.0)
+        .apply(&stack)
+        .unwrap();
+    let ring = variant.dims[2].reviewed().unwrap();
+    assert!(matches!(
+        ring.distribution,
+        Some(dist::Distribution::Uniform(_))
+    ));
+    assert_eq!(
+        variant.dims[0].reviewed().unwrap().target_process_sigma,
+        4.0
+    );
+
+    assert!(matches!(
+        Scenario::new("bad").process_sigma("cover", 3.0).apply(&stack),
+        Err(Error::UnknownDim(name)) if name == "cover"
+    ));
+}
+
+#[test]
+fn test_comparison() {
+    let scenarios = [
+        Scenario::new("case 0.1").tolerance("case", Bilateral::symmetric(0.1)),
+        Scenario::new("shaft 4s").process_sigma("shaft", 4.0),
+    ];
+    let comparison = Comparison::new(&stack(), &scenarios, &requirement(), 3.0).unwrap();
+    assert_eq!(comparison.scenarios.len(), 2);
+
+    let deltas = comparison.deltas();
+    assert_eq!(deltas[0].name, "case 0.1");
+    assert!((deltas[0].wc - -0.09).abs() < 1e-12);
+    assert!((deltas[0].closed - -0.09).abs() < 1e-12);
+    let rss = |t: f64| 2.0 * (0.036f64.powi(2) + t * t + 0.03f64.powi(2)).sqrt();
+    assert!((deltas[0].rss - (rss(0.1) - rss(0.145))).abs() < 1e-12);
+    assert!(deltas[0].six_sigma.unwrap() < 0.0);
+    assert!(deltas[0].yield_probability.unwrap() > 0.0);
+    // a more capable shaft leaves the design tolerances alone
+    assert_eq!(deltas[1].wc, 0.0);
+    assert!(deltas[1].six_sigma.unwrap() < 0.0);
+
+    let table = comparison.to_string();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert!(lines[0].contains("baseline") && lines[0].contains("case 0.1"));
+    assert!(lines[2].starts_with("WC") && lines[2].contains("0.3320 (-0.0900)"));
+    assert!(lines[6].starts_with("Reject PPM"));
+}
@@ -0,0 +1,86 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::nonlinear::NonlinearStack;
+
+fn close(x: f64, expected: f64) -> bool {
+    (x - expected).abs() < 0.03
+}
+
+#[test]
+fn test_sobol_linear() {
+    let stack: dim::Stack = dim::Stack::builder("gap")
+        .push(dim::Basic::builder("housing").nominal(20.0).sym(0.3))
+        .push(dim::Basic::builder("bearing").nominal(-8.0).sym(0.4))
+        .push(dim::Basic::builder("cover").nominal(-11.0).sym(0.0))
+        .build();
+    let indices = calc::sobol(&stack, 20000, 1).unwrap();
+    assert_eq!(indices.len(), 3);
+    assert_eq!(indices[1].name, "bearing");
+    // independent linear dims: both indices are the RSS variance shares
+    for (index, contribution) in indices.iter().zip(calc::contributions(&stack).unwrap()) {
+        assert!(close(index.first_order, contribution.rss));
+        assert!(close(index.total, contribution.rss));
+    }
+    assert_eq!(indices[2].first_order, 0.0);
+    assert_eq!(indices[2].total, 0.0);
+    // seeded
+    assert_eq!(calc::sobol(&stack, 20000, 1).unwrap(), indices);
+}
+
+#[test]
+fn test_sobol_nonlinear() {
+    // f = a b with a ~ N(0, 1) and b ~ N(1, 1): Var f = 2, E[f | a] = a and
+    // E[f | b] = 0, so all of the effect of b is through its interaction
+    let stack: NonlinearStack = NonlinearStack::builder("product", |x: &[f64]| x[0] * x[1])
+        .push(dim::Basic::builder("a").nominal(0.0).sym(3.0))
+        .push(dim::Basic::builder("b").nominal(1.0).sym(3.0))
+        .build();
+    let indices = stack.sobol(20000, 2).unwrap();
+    assert!(close(indices[0].first_order, 0.5));
+    assert!(close(indices[0].total, 1.0));
+    assert!(close(indices[1].first_order, 0.0));
+    assert!(close(indices[1].total, 0.5));
+}
+
+#[test]
+fn test_sobol_correlated() {
+    // f = a + b with unit normals correlated at 0.6: E[f | a] = 1.6 a, so
+    // the first order index is 1.6^2 / 3.2 and the total 0.64 / 3.2
+    let mut stack: dim::Stack = dim::Stack::builder("pair")
+        .push(dim::Basic::builder("a").nominal(10.0).sym(3.0))
+        .push(dim::Basic::builder("b").nominal(5.0).sym(3.0))
+        .build();
+    stack.correlate("a", "b", 0.6).unwrap();
+    let indices = calc::sobol(&stack, 20000, 3).unwrap();
+    for index in &indices {
+        assert!(close(index.first_order, 0.8));
+        assert!(close(index.total, 0.2));
+    }
+
+    let mut stack: NonlinearStack = NonlinearStack::builder("pair", |x: &[f64]| x[0] + x[1])
+        .push(dim::Basic::builder("a").nominal(10.0).sym(3.0))
+        .push(dim::Basic::builder("b").nominal(5.0).sym(3.0))
+        .build();
+    stack.correlate("a", "b", 0.6).unwrap();
+    let indices = stack.sobol(20000, 3).unwrap();
+    assert!(close(indices[0].first_order, 0.8));
+    assert!(close(indices[1].total, 0.2));
+    assert_eq!(
+        stack.correlate("a", "c", 0.5),
+        Err(Error::UnknownDim("c".to_owned()))
+    );
+}
+
+#[test]
+fn test_sobol_errors() {
+    let empty: dim::Stack = dim::Stack::builder("empty").build();
+    assert!(matches!(
+        calc::sobol(&empty, 100, 0),
+        Err(Error::TooFewDims { .. })
+    ));
+    let stack: dim::Stack = dim::Stack::builder("one")
+        .push(dim::Basic::builder("a").nominal(1.0).sym(0.1))
+        .build();
+    assert_eq!(calc::sobol(&stack, 1, 0).err(), Some(Error::EmptyInput));
+}
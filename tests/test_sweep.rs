@@ -0,0 +1,104 @@
+use dimstack_rs::dim;
+use dimstack_rs::dist;
+use dimstack_rs::error::Error;
+use dimstack_rs::sweep::{Parameter, Sweep};
+
+fn stack() -> dim::Stack {
+    dim::Stack::builder("bearing")
+        .push(
+            dim::Basic::builder("shaft")
+                .nominal(208.0)
+                .sym(0.036)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(
+            dim::Basic::builder("case")
+                .nominal(-200.0)
+                .sym(0.145)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(
+            dim::Basic::builder("ring")
+                .nominal(-7.75)
+                .sym(0.03)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build()
+}
+
+fn requirement() -> dim::Requirement {
+    dim::Requirement::new(
+        "end play".to_owned(),
+        "".to_owned(),
+        dist::Distribution::Normal(dist::Normal::new(0.25, 0.1).unwrap()),
+        0.1,
+        0.4,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_sweep_tolerance() {
+    let sweep: Sweep = Sweep::linear("case", Parameter::Tolerance, 0.05, 0.15, 5);
+    assert_eq!(sweep.values.len(), 5);
+    assert!((sweep.values[1] - 0.075).abs() < 1e-12);
+    let result = sweep.run(&stack(), &requirement(), 3.0).unwrap();
+    assert_eq!(result.points.len(), 5);
+    let (value, first) = &result.points[0];
+    assert_eq!(*value, 0.05);
+    assert!((first.wc.tol.t() - 2.0 * (0.036 + 0.05 + 0.03)).abs() < 1e-12);
+
+    // rejects grow with the tolerance
+    let ppm = result.reject_ppm();
+    assert_eq!(ppm.len(), 5);
+    assert!(ppm.windows(2).all(|w| w[1].1 > w[0].1));
+
+    let table = result.to_string();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert!(lines[0].contains("tolerance") && lines[0].contains("Reject PPM"));
+    assert!(lines[1].trim_start().starts_with("0.05"));
+
+    let svg = result.svg();
+    assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("case tolerance sweep"));
+    assert_eq!(svg.matches("<circle").count(), 5);
+}
+
+#[test]
+fn test_sweep_nominal_and_sigma() {
+    let sweep = Sweep::new("ring", Parameter::Nominal, vec![-7.8, -7.75, -7.7]);
+    let result = sweep.run(&stack(), &requirement(), 3.0).unwrap();
+    let medians: Vec<f64> = result.points.iter().map(|(_, o)| o.wc.nom.x).collect();
+    assert!((medians[0] - 0.2).abs() < 1e-9);
+    assert!((medians[2] - 0.3).abs() < 1e-9);
+    // centered in the requirement at the middle value
+    let ppm = result.reject_ppm();
+    assert!(ppm[1].1 < ppm[0].1 && ppm[1].1 < ppm[2].1);
+
+    let sweep = Sweep::new("case", Parameter::TargetProcessSigma, vec![3.0, 4.0, 5.0]);
+    let result = sweep.run(&stack(), &requirement(), 3.0).unwrap();
+    let six_sigma: Vec<f64> = result
+        .points
+        .iter()
+        .map(|(_, o)| o.six_sigma.as_ref().unwrap().dim.tol.t())
+        .collect();
+    assert!(six_sigma[0] > six_sigma[1] && six_sigma[1] > six_sigma[2]);
+
+    assert_eq!(
+        Sweep::new("case", Parameter::Nominal, vec![])
+            .run(&stack(), &requirement(), 3.0)
+            .err(),
+        Some(Error::EmptyInput)
+    );
+    assert!(matches!(
+        Sweep::new("cover", Parameter::Nominal, vec![1.0]).run(&stack(), &requirement(), 3.0),
+        Err(Error::UnknownDim(_))
+    ));
+}
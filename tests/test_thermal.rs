@@ -0,0 +1,141 @@
+use dimstack_rs::calc;
+use dimstack_rs::dim;
+use dimstack_rs::error::Error;
+use dimstack_rs::material::Material;
+use dimstack_rs::part::{self, Part};
+use dimstack_rs::thermal::Thermal;
+
+fn shaft_in_case() -> dim::Stack {
+    dim::Stack::builder("end play")
+        .push(
+            dim::Basic::builder("case")
+                .nominal(100.0)
+                .sym(0.05)
+                .material(Material::named("6061-T6").unwrap()),
+        )
+        .push(
+            dim::Basic::builder("shaft")
+                .nominal(-99.5)
+                .sym(0.05)
+                .material(Material::named("1018").unwrap()),
+        )
+        .build()
+}
+
+#[test]
+fn test_thermal_shifts_gap() {
+    let stack = shaft_in_case();
+    let thermal = Thermal::new(20.0, -20.0, 120.0).unwrap();
+
+    let hot = calc::wc(&thermal.at(&stack, 120.0));
+    let growth = 100.0 * 23.6e-6 * 100.0 - 99.5 * 12e-6 * 100.0;
+    assert!((hot.nom.x - (0.5 + growth)).abs() < 1e-12);
+
+    // the window spans the cold lower limit to the hot upper limit
+    let wc = thermal.wc(&stack);
+    let cold = calc::wc(&thermal.at(&stack, -20.0));
+    assert!((wc.nom.x - 0.5).abs() < 1e-12);
+    assert!((wc.absolute_lower() - cold.absolute_lower()).abs() < 1e-12);
+    assert!((wc.absolute_upper() - hot.absolute_upper()).abs() < 1e-12);
+    assert!(wc.tol.t() > calc::wc(&stack).tol.t() + growth);
+
+    let rss = thermal.rss(&stack).unwrap();
+    assert!(rss.tol.t() < wc.tol.t());
+    assert!(rss.absolute_upper() > calc::rss(&stack).unwrap().absolute_upper());
+}
+
+#[test]
+fn test_thermal_reviewed_and_plain_dims() {
+    let stack: dim::Stack = dim::Stack::builder("mixed")
+        .push(
+            dim::Basic::builder("case")
+                .nominal(50.0)
+                .sym(0.06)
+                .material(Material::named("6061-T6").unwrap())
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .push(
+            dim::Basic::builder("spacer")
+                .nominal(-49.0)
+                .sym(0.06)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build();
+    let thermal: Thermal = Thermal::new(20.0, 20.0, 70.0).unwrap();
+    let hot = thermal.at(&stack, 70.0);
+    let case = hot.dims[0].reviewed().unwrap();
+    let mean = case.distribution.as_ref().unwrap().mean();
+    assert!((mean - 50.0 * (1.0 + 23.6e-6 * 50.0)).abs() < 1e-12);
+    assert_eq!(hot.dims[1].basic().nom.x, -49.0);
+
+    let six_sigma = thermal.six_sigma(&stack, 3.0).unwrap();
+    assert!(
+        (six_sigma.absolute_upper() - calc::six_sigma(&hot, 3.0).unwrap().dim.absolute_upper())
+            .abs()
+            < 1e-12
+    );
+
+    assert_eq!(
+        Thermal::new(20.0, 70.0, 20.0).err(),
+        Some(Error::InvertedLimits {
+            lower: 70.0,
+            upper: 20.0
+        })
+    );
+}
+
+#[test]
+fn test_thermal_keeps_correlation() {
+    let mut stack = shaft_in_case();
+    stack.correlate("case", "shaft", 0.5).unwrap();
+    let thermal = Thermal::new(20.0, -20.0, 120.0).unwrap();
+    let hot = thermal.at(&stack, 120.0);
+    assert_eq!(hot.correlation().unwrap().unwrap().get(0, 1), 0.5);
+    assert!(thermal.rss(&stack).unwrap().tol.t() < thermal.rss(&shaft_in_case()).unwrap().tol.t());
+}
+
+#[test]
+fn test_thermal_keeps_parts() {
+    let housing = Part::new("100-2001", "Housing");
+    let stack = dim::Stack::builder("end play")
+        .push(
+            dim::Basic::builder("bore depth")
+                .nominal(20.0)
+                .sym(0.3)
+                .material(Material::named("6061-T6").unwrap())
+                .part(housing.clone())
+                .feature("bearing bore"),
+        )
+        .push(
+            dim::Basic::builder("shaft")
+                .nominal(-8.0)
+                .sym(0.2)
+                .material(Material::named("1018").unwrap())
+                .part(Part::new("200-0001", "Shaft")),
+        )
+        .push(
+            dim::Basic::builder("cover step")
+                .nominal(-11.0)
+                .sym(0.3)
+                .material(Material::named("6061-T6").unwrap())
+                .part(housing),
+        )
+        .build();
+    let thermal = Thermal::new(20.0, -20.0, 120.0).unwrap();
+    let hot = thermal.at(&stack, 120.0);
+    assert_eq!(hot.dims[0].basic().feature.as_deref(), Some("bearing bore"));
+
+    let groups = |stack: &dim::Stack| -> Vec<(Option<String>, Vec<String>)> {
+        part::by_part(stack)
+            .unwrap()
+            .into_iter()
+            .map(|share| (share.part.map(|part| part.number), share.dims))
+            .collect()
+    };
+    assert_eq!(groups(&hot), groups(&thermal.at(&stack, 20.0)));
+    assert_eq!(groups(&hot).len(), 2);
+}
@@ -0,0 +1,132 @@
+use dimstack_rs::validate::Warning;
+use dimstack_rs::{dim, dist, tol};
+
+fn basic(name: &str, nom: f64, tol: tol::Bilateral) -> dim::Basic {
+    dim::Basic::builder(name).nominal(nom).tol(tol).build()
+}
+
+#[test]
+fn test_validate_clean_stack() {
+    let stack = dim::Stack::new(
+        "clean".to_owned(),
+        "".to_owned(),
+        vec![
+            basic("Shaft", 208.0, tol::Bilateral::symmetric(0.036)).into(),
+            basic("Case", -200.0, tol::Bilateral::symmetric(0.145)).into(),
+            basic("Ring", -7.75, tol::Bilateral::unequal(0.0, -0.06)).into(),
+        ],
+    );
+    assert!(stack.validate().is_empty());
+}
+
+#[test]
+fn test_validate_dims() {
+    let stack = dim::Stack::new(
+        "bad".to_owned(),
+        "".to_owned(),
+        vec![
+            basic("Shaft", 208.0, tol::Bilateral::symmetric(0.036)).into(),
+            basic("Shaft", 20.0, tol::Bilateral::symmetric(0.026)).into(),
+            dim::Basic::builder("Washer")
+                .nominal(-0.032)
+                .sym(0.05)
+                .sensitivity(0.0)
+                .into(),
+        ],
+    );
+    let warnings = stack.validate();
+    assert!(warnings.contains(&Warning::DuplicateName("Shaft".to_owned())));
+    assert!(warnings.contains(&Warning::ZeroSensitivity("Washer".to_owned())));
+    assert!(warnings.contains(&Warning::ToleranceExceedsNominal("Washer".to_owned())));
+    assert!(warnings.contains(&Warning::OpenLoop));
+}
+
+#[test]
+fn test_validate_closure() {
+    // the case was entered with the wrong direction
+    let stack = dim::Stack::new(
+        "flipped".to_owned(),
+        "".to_owned(),
+        vec![
+            basic("Shaft", 208.0, tol::Bilateral::symmetric(0.036)).into(),
+            basic("Case", 200.0, tol::Bilateral::symmetric(0.145)).into(),
+            basic("Bearing", -23.0, tol::Bilateral::unequal(0.0, -0.12)).into(),
+        ],
+    );
+    assert_eq!(stack.validate(), vec![Warning::SuspiciousClosure(385.0)]);
+}
+
+#[test]
+fn test_validate_reviewed() {
+    let shaft =
+        dim::Reviewed::of_basic(basic("Shaft", 208.0, tol::Bilateral::symmetric(0.036)), 6.0)
+            .unwrap();
+    let case = dim::Reviewed::new(
+        basic("Case", -200.0, tol::Bilateral::symmetric(0.145)),
+        3.0,
+        Some(dist::Distribution::Normal(
+            dist::Normal::new(-199.8, 0.05).unwrap(),
+        )),
+    )
+    .unwrap();
+    let stack = dim::Stack::builder("reviewed")
+        .push(shaft)
+        .push(case)
+        .build();
+    let warnings = stack.validate();
+    assert!(warnings.contains(&Warning::MeanOutsideLimits("Case".to_owned())));
+    assert!(warnings.contains(&Warning::MixedProcessSigma(vec![6.0, 3.0])));
+}
+
+fn uniform(name: &str, nom: f64, tol: f64) -> dim::Reviewed {
+    let lower = nom.abs() - tol;
+    let upper = nom.abs() + tol;
+    let (lower, upper) = if nom > 0.0 {
+        (lower, upper)
+    } else {
+        (-upper, -lower)
+    };
+    dim::Basic::builder(name)
+        .nominal(nom)
+        .sym(tol)
+        .reviewed(3.0)
+        .distribution(dist::Distribution::Uniform(
+            dist::Uniform::new(lower, upper).unwrap(),
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_validate_non_normal_assembly() {
+    let stack = dim::Stack::builder("uniforms")
+        .push(uniform("a", 10.0, 0.1))
+        .push(uniform("b", -5.0, 0.1))
+        .build();
+    let warnings = stack.validate();
+    assert_eq!(warnings.len(), 1);
+    match &warnings[0] {
+        Warning::NonNormalAssembly {
+            skewness,
+            excess_kurtosis,
+        } => {
+            assert!(skewness.abs() < 1e-9);
+            assert!((excess_kurtosis + 0.6).abs() < 1e-9);
+        }
+        warning => panic!("unexpected {warning}"),
+    }
+
+    // a small uniform among normal dims is fine
+    let stack = dim::Stack::builder("mixed")
+        .push(uniform("a", 10.0, 0.01))
+        .push(
+            dim::Basic::builder("b")
+                .nominal(-5.0)
+                .sym(0.1)
+                .reviewed(3.0)
+                .build()
+                .unwrap(),
+        )
+        .build();
+    assert!(stack.validate().is_empty());
+}
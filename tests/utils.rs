@@ -1,9 +1,15 @@
 #[cfg(test)]
 #[macro_export]
 macro_rules! assert_delta {
-    ($x:expr, $y:expr, $d:expr) => {
-        if !($x - $y < $d || $y - $x < $d) {
-            panic!();
+    ($x:expr, $y:expr, $d:expr) => {{
+        let diff: f64 = ($x) - ($y);
+        if diff.is_nan() || diff.abs() >= $d {
+            panic!(
+                "assertion failed: |{} - {}| < {}",
+                stringify!($x),
+                stringify!($y),
+                $d
+            );
         }
-    };
+    }};
 }